@@ -0,0 +1,62 @@
+//! Compares dumping a file by reading it with dumping it from a memory map, for sizes around
+//! `MMAP_THRESHOLD` and a larger file. Run with `cargo bench --bench input`.
+
+use std::fs::File;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hexyl::{PrinterBuilder, MMAP_THRESHOLD};
+
+const SIZES: [u64; 3] = [256 * 1024, MMAP_THRESHOLD, 16 * 1024 * 1024];
+
+/// Creates a file of `size` bytes of text and binary data, so that the dump has all colors.
+fn input_file(size: u64) -> PathBuf {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("input-{size}"));
+    if path
+        .metadata()
+        .map_or(true, |metadata| metadata.len() != size)
+    {
+        let mut file = io::BufWriter::new(File::create(&path).unwrap());
+        let mut state = 0x2545_f491_u32;
+        for _ in 0..size {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            file.write_all(&[(state % 160) as u8]).unwrap();
+        }
+        file.flush().unwrap();
+    }
+    path
+}
+
+fn dump_file(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dump_file");
+    group.sample_size(10);
+    for size in SIZES {
+        let mut file = File::open(input_file(size)).unwrap();
+        group.throughput(Throughput::Bytes(size));
+        group.bench_with_input(BenchmarkId::new("read", size), &size, |b, _| {
+            b.iter(|| {
+                file.seek(SeekFrom::Start(0)).unwrap();
+                PrinterBuilder::new(io::sink())
+                    .build()
+                    .print_all(&mut file)
+                    .unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("mmap", size), &size, |b, _| {
+            b.iter(|| {
+                file.seek(SeekFrom::Start(0)).unwrap();
+                PrinterBuilder::new(io::sink())
+                    .build()
+                    .print_file(&mut file, None)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, dump_file);
+criterion_main!(benches);
@@ -0,0 +1,52 @@
+//! Measures how fast dumps are rendered, with and without colors, and on one thread per CPU.
+//! Run with `cargo bench --bench render`.
+
+use std::io;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hexyl::{Endianness, PrinterBuilder};
+
+const SIZE: usize = 4 * 1024 * 1024;
+
+/// Text and binary data, so that the dump has all colors and no squeezed rows.
+fn input() -> Vec<u8> {
+    let mut state = 0x2545_f491_u32;
+    (0..SIZE)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state % 160) as u8
+        })
+        .collect()
+}
+
+fn render(c: &mut Criterion) {
+    let input = input();
+    let mut group = c.benchmark_group("render");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(SIZE as u64));
+    for (name, show_color, endianness, threads) in [
+        ("plain", false, Endianness::Big, 1),
+        ("color", true, Endianness::Big, 1),
+        ("color_little_endian", true, Endianness::Little, 1),
+        ("color_threads", true, Endianness::Big, 0),
+    ] {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &input, |b, input| {
+            b.iter(|| {
+                PrinterBuilder::new(io::sink())
+                    .show_color(show_color)
+                    .group_size(4)
+                    .endianness(endianness)
+                    .threads(threads)
+                    .build()
+                    .print_all(&input[..])
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, render);
+criterion_main!(benches);
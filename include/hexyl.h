@@ -0,0 +1,124 @@
+#ifndef HEXYL_H
+#define HEXYL_H
+
+/* Generated with cbindgen from src/ffi.rs, do not edit. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#define HEXYL_BASE_BINARY 0
+
+#define HEXYL_BASE_OCTAL 1
+
+#define HEXYL_BASE_DECIMAL 2
+
+#define HEXYL_BASE_HEXADECIMAL 3
+
+#define HEXYL_ENDIANNESS_LITTLE 0
+
+#define HEXYL_ENDIANNESS_BIG 1
+
+#define HEXYL_CHARACTER_TABLE_DEFAULT 0
+
+#define HEXYL_CHARACTER_TABLE_ASCII 1
+
+#define HEXYL_CHARACTER_TABLE_CP1047 2
+
+#define HEXYL_CHARACTER_TABLE_CP437 3
+
+#define HEXYL_BORDER_STYLE_UNICODE 0
+
+#define HEXYL_BORDER_STYLE_ASCII 1
+
+#define HEXYL_BORDER_STYLE_NONE 2
+
+/*
+ A printer that writes a dump into a callback.
+ */
+typedef struct HexylPrinter HexylPrinter;
+
+/*
+ The settings of a printer, see `PrinterConfig`. The enums are one of the `HEXYL_*`
+ constants.
+ */
+typedef struct HexylConfig {
+  uint64_t panels;
+  uint8_t group_size;
+  uint8_t base;
+  uint8_t endianness;
+  uint8_t character_table;
+  uint8_t border_style;
+  bool show_color;
+  bool show_char_panel;
+  bool show_position_panel;
+  bool squeeze;
+} HexylConfig;
+
+/*
+ Receives the output of a printer. It returns 0 if all `len` bytes were written, anything
+ else fails the call of the printer that wrote them.
+ */
+typedef int (*HexylWriteCallback)(void *user_data, const uint8_t *bytes, size_t len);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Sets `config` to the defaults of the printer.
+
+ # Safety
+
+ `config` must be valid for writes.
+ */
+void hexyl_config_default(struct HexylConfig *config);
+
+/*
+ Creates a printer that writes into `callback`, which gets `user_data` with every call. A null
+ `config` uses the defaults. Returns null if the configuration is invalid.
+
+ # Safety
+
+ `config` must be null or valid for reads. `callback` must be callable with `user_data` until
+ the printer is freed.
+ */
+struct HexylPrinter *hexyl_printer_new(const struct HexylConfig *config,
+                                       HexylWriteCallback callback,
+                                       void *user_data);
+
+/*
+ Prints `len` bytes of the input, which are dumped as soon as a row is complete. Returns 0, or
+ -1 if the write callback failed.
+
+ # Safety
+
+ `printer` must come from `hexyl_printer_new`, and `bytes` must be valid for reads of `len`
+ bytes.
+ */
+int hexyl_printer_write(struct HexylPrinter *printer, const uint8_t *bytes, size_t len);
+
+/*
+ Prints the rest of the dump. Bytes that are written afterwards start a new dump. Returns 0,
+ or -1 if the write callback failed.
+
+ # Safety
+
+ `printer` must come from `hexyl_printer_new`.
+ */
+int hexyl_printer_finish(struct HexylPrinter *printer);
+
+/*
+ Frees a printer without finishing its dump. Null is ignored.
+
+ # Safety
+
+ `printer` must be null or come from `hexyl_printer_new`, and must not be used afterwards.
+ */
+void hexyl_printer_free(struct HexylPrinter *printer);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* HEXYL_H */
@@ -0,0 +1,101 @@
+use std::io::{self, Write};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::io::read_error;
+use crate::{ConfigError, DumpSummary, Printer, PrinterBuilder};
+
+/// The number of bytes to read at once, same as the buffer of `Printer::print_all`.
+const BUFFER_SIZE: usize = 8 * 1024;
+
+impl<Writer: AsyncWrite + Unpin> PrinterBuilder<Writer> {
+    /// Builds a printer that reads from an [`AsyncRead`] and writes into the [`AsyncWrite`] of the
+    /// builder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is invalid, see [`PrinterBuilder::try_build_async`].
+    pub fn build_async(self) -> AsyncPrinter<Writer> {
+        match self.try_build_async() {
+            Ok(printer) => printer,
+            Err(e) => panic!("invalid printer configuration: {e}"),
+        }
+    }
+
+    /// Builds the asynchronous printer, or returns why the configuration is invalid.
+    pub fn try_build_async(self) -> Result<AsyncPrinter<Writer>, ConfigError> {
+        let (builder, writer) = self.replace_writer(vec![]);
+        Ok(AsyncPrinter {
+            printer: builder.try_build()?,
+            writer,
+        })
+    }
+}
+
+/// The asynchronous counterpart of [`Printer`]. The dump is rendered by a `Printer` into a buffer
+/// that is then written asynchronously, so the output is the same.
+pub struct AsyncPrinter<Writer: AsyncWrite + Unpin> {
+    printer: Printer<Vec<u8>>,
+    writer: Writer,
+}
+
+impl<Writer: AsyncWrite + Unpin> AsyncPrinter<Writer> {
+    pub fn display_offset(&mut self, display_offset: u64) -> &mut Self {
+        self.printer.display_offset(display_offset);
+        self
+    }
+
+    /// The summary of the current dump, or of the last one if it has ended.
+    pub fn summary(&self) -> &DumpSummary {
+        self.printer.summary()
+    }
+
+    /// Loop through the given `Reader`, printing until the `Reader` buffer
+    /// is exhausted. Returns the summary of the dump, and read errors are handled like in
+    /// [`Printer::print_all`].
+    pub async fn print_all<Reader: AsyncRead + Unpin>(
+        &mut self,
+        mut reader: Reader,
+    ) -> io::Result<DumpSummary> {
+        let mut buf = vec![0; BUFFER_SIZE];
+        loop {
+            match reader.read(&mut buf).await {
+                Ok(0) => break,
+                Ok(n) => self.write(&buf[..n]).await?,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    let offset = self.printer.position();
+                    if self.printer.is_started() {
+                        self.finish().await?;
+                    }
+                    return Err(read_error(offset, e));
+                }
+            }
+        }
+        self.finish().await?;
+        Ok(self.summary().clone())
+    }
+
+    /// Feeds the input of the dump and prints the rows it completes, like writing to a
+    /// [`Printer`].
+    pub async fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.printer.write_all(data)?;
+        self.write_output().await
+    }
+
+    /// Ends the dump, like [`Printer::finish`].
+    pub async fn finish(&mut self) -> io::Result<()> {
+        self.printer.finish()?;
+        self.write_output().await?;
+        self.writer.flush().await
+    }
+
+    async fn write_output(&mut self) -> io::Result<()> {
+        let output = self.printer.writer_mut();
+        if !output.is_empty() {
+            self.writer.write_all(output).await?;
+            output.clear();
+        }
+        Ok(())
+    }
+}
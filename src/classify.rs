@@ -0,0 +1,64 @@
+use crate::{Byte, ColorType};
+
+/// How a byte is shown in the dump.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Style {
+    /// The color of the byte in the hex and character panels.
+    pub color: ColorType,
+    /// The character in the character panel, `None` for the one of the character table.
+    pub glyph: Option<char>,
+}
+
+impl Style {
+    /// A style with `color` and the glyph of the character table.
+    pub const fn new(color: ColorType) -> Self {
+        Style { color, glyph: None }
+    }
+
+    /// Shows the byte as `glyph` in the character panel.
+    pub const fn with_glyph(mut self, glyph: char) -> Self {
+        self.glyph = Some(glyph);
+        self
+    }
+}
+
+/// Decides how every byte of a dump is shown, see [`PrinterBuilder::byte_classifier`].
+///
+/// It is implemented for closures, so that a classifier can fall back to the default one:
+///
+/// ```
+/// use hexyl::{ByteClassifier, ColorType, DefaultClassifier, PrinterBuilder, Style};
+///
+/// let mut output = vec![];
+/// let mut printer = PrinterBuilder::new(&mut output)
+///     .byte_classifier(|offset, byte| match byte {
+///         0xff => Style::new(ColorType::Rgb(0x80, 0x80, 0x80)).with_glyph('░'),
+///         _ => DefaultClassifier.style(offset, byte),
+///     })
+///     .build();
+/// printer.print_all(&[0xff, 0x00, b'h', b'i'][..]).unwrap();
+/// ```
+///
+/// [`PrinterBuilder::byte_classifier`]: crate::PrinterBuilder::byte_classifier
+pub trait ByteClassifier {
+    /// The style of `byte`, which is at `offset` including the display offset.
+    fn style(&self, offset: u64, byte: u8) -> Style;
+}
+
+impl<F: Fn(u64, u8) -> Style> ByteClassifier for F {
+    fn style(&self, offset: u64, byte: u8) -> Style {
+        self(offset, byte)
+    }
+}
+
+/// The classifier of hexyl, which colors bytes by their [`ByteCategory`].
+///
+/// [`ByteCategory`]: crate::ByteCategory
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultClassifier;
+
+impl ByteClassifier for DefaultClassifier {
+    fn style(&self, _offset: u64, byte: u8) -> Style {
+        Style::new(Byte(byte).color())
+    }
+}
@@ -6,8 +6,18 @@ const COLOR_ASCII_PRINTABLE: &[u8] = colors::Cyan::ANSI_FG.as_bytes();
 const COLOR_ASCII_WHITESPACE: &[u8] = colors::Green::ANSI_FG.as_bytes();
 const COLOR_ASCII_OTHER: &[u8] = colors::Magenta::ANSI_FG.as_bytes();
 const COLOR_NONASCII: &[u8] = colors::Yellow::ANSI_FG.as_bytes();
+const COLOR_UNREADABLE: &[u8] = colors::Red::ANSI_FG.as_bytes();
 const COLOR_RESET: &[u8] = colors::Default::ANSI_FG.as_bytes();
 
+// the same colors for HTML output, using the xterm palette for the basic ANSI colors
+const CSS_NULL: &str = "#6c6c6c";
+const CSS_OFFSET: &str = "#6c6c6c";
+const CSS_ASCII_PRINTABLE: &str = "#00cdcd";
+const CSS_ASCII_WHITESPACE: &str = "#00cd00";
+const CSS_ASCII_OTHER: &str = "#cd00cd";
+const CSS_NONASCII: &str = "#cdcd00";
+const CSS_UNREADABLE: &str = "#cd0000";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ColorType {
     Null,
@@ -16,10 +26,16 @@ pub enum ColorType {
     AsciiWhitespace,
     AsciiOther,
     NonAscii,
+    /// Bytes that could not be read.
+    Unreadable,
+    /// A 24-bit color, e.g. of a custom [`Style`](crate::Style).
+    Rgb(u8, u8, u8),
     Reset,
 }
 
 impl ColorType {
+    /// The ANSI escape sequence of the color. It is empty for [`ColorType::Rgb`], whose sequence
+    /// is not a constant.
     pub const fn ansi_bytes(self) -> &'static [u8] {
         match self {
             Self::Null => COLOR_NULL,
@@ -28,9 +44,39 @@ impl ColorType {
             Self::AsciiWhitespace => COLOR_ASCII_WHITESPACE,
             Self::AsciiOther => COLOR_ASCII_OTHER,
             Self::NonAscii => COLOR_NONASCII,
+            Self::Unreadable => COLOR_UNREADABLE,
+            Self::Rgb(..) => b"",
             Self::Reset => COLOR_RESET,
         }
     }
+
+    /// The CSS class of the color in HTML output, `None` for [`ColorType::Reset`] and
+    /// [`ColorType::Rgb`], which is written as a `style` attribute.
+    pub const fn css_class(self) -> Option<&'static str> {
+        match self {
+            Self::Null => Some("null"),
+            Self::Offset => Some("offset"),
+            Self::AsciiPrintable => Some("ascii-printable"),
+            Self::AsciiWhitespace => Some("ascii-whitespace"),
+            Self::AsciiOther => Some("ascii-other"),
+            Self::NonAscii => Some("non-ascii"),
+            Self::Unreadable => Some("unreadable"),
+            Self::Rgb(..) | Self::Reset => None,
+        }
+    }
+
+    pub(crate) const fn css_color(self) -> Option<&'static str> {
+        match self {
+            Self::Null => Some(CSS_NULL),
+            Self::Offset => Some(CSS_OFFSET),
+            Self::AsciiPrintable => Some(CSS_ASCII_PRINTABLE),
+            Self::AsciiWhitespace => Some(CSS_ASCII_WHITESPACE),
+            Self::AsciiOther => Some(CSS_ASCII_OTHER),
+            Self::NonAscii => Some(CSS_NONASCII),
+            Self::Unreadable => Some(CSS_UNREADABLE),
+            Self::Rgb(..) | Self::Reset => None,
+        }
+    }
 }
 
 #[rustfmt::skip]
@@ -0,0 +1,518 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use crate::io::{self, Read, Write};
+
+/// What kind of output to produce from the input.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[non_exhaustive]
+pub enum OutputFormat {
+    /// A hex dump in the selected layout.
+    #[default]
+    Dump,
+
+    /// A JSON array with one object per row of the dump.
+    Json,
+
+    /// One JSON object per row of the dump and per line of output.
+    Ndjson,
+
+    /// A standalone HTML document of the dump, with a CSS class per byte category.
+    Html,
+
+    /// Only the `<pre>` element of the HTML document, without the stylesheet.
+    HtmlFragment,
+
+    /// A continuous hex string without any spacing, like 'xxd -p'.
+    PlainHex,
+
+    /// An ANSI-C quoted string for bash and zsh: $'\x89PNG\r\n'. Fails on NUL
+    /// bytes, which end such a string in bash.
+    ShellString,
+
+    /// A C string literal: "\x89PNG\r\n".
+    CString,
+
+    /// A Python bytes literal: b'\x89PNG\r\n'.
+    PythonString,
+
+    /// A Rust byte string literal: b"\x89PNG\r\n".
+    RustString,
+
+    /// A C array, like 'xxd -i': const uint8_t data[] = {0x89, ...};
+    CArray,
+
+    /// A Rust constant: const DATA: &[u8; 29] = &[0x89, ...];
+    RustArray,
+
+    /// A Python bytes object: data = bytes([0x89, ...])
+    PythonArray,
+
+    /// A Go byte slice: var data = []byte{0x89, ...}
+    GoArray,
+}
+
+impl OutputFormat {
+    pub(crate) fn is_json(self) -> bool {
+        matches!(self, OutputFormat::Json | OutputFormat::Ndjson)
+    }
+
+    pub(crate) fn is_html(self) -> bool {
+        matches!(self, OutputFormat::Html | OutputFormat::HtmlFragment)
+    }
+
+    /// Whether the format is an array of the bytes in source code, which has a variable name.
+    pub(crate) fn is_array(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::CArray
+                | OutputFormat::RustArray
+                | OutputFormat::PythonArray
+                | OutputFormat::GoArray
+        )
+    }
+
+    /// Whether the format is split into lines of a number of bytes.
+    pub(crate) fn has_lines(self) -> bool {
+        self == OutputFormat::PlainHex || self.is_array()
+    }
+
+    /// Whether the format can be written piece by piece with [`ExportPrinter::push`], instead of
+    /// needing the whole input.
+    pub(crate) fn is_streamed(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::PlainHex
+                | OutputFormat::ShellString
+                | OutputFormat::CString
+                | OutputFormat::PythonString
+                | OutputFormat::RustString
+        )
+    }
+
+    /// Whether the format is rendered row by row by `Printer` instead of by `ExportPrinter`.
+    pub(crate) fn has_rows(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Dump
+                | OutputFormat::Json
+                | OutputFormat::Ndjson
+                | OutputFormat::Html
+                | OutputFormat::HtmlFragment
+        )
+    }
+}
+
+/// The default number of bytes per line of the plain hex format, same as `xxd -p`.
+pub(crate) const PLAIN_HEX_BYTES_PER_LINE: usize = 30;
+
+/// The default number of bytes per line of the array formats, same as `xxd -i`.
+pub(crate) const ARRAY_BYTES_PER_LINE: usize = 12;
+
+/// The name of the array variable if none is given.
+pub(crate) const DEFAULT_VARIABLE_NAME: &str = "data";
+
+/// The state of a format that is written piece by piece, see [`ExportPrinter::push`].
+#[derive(Default)]
+pub(crate) struct StreamState {
+    /// Whether the beginning of the output is written, like the prefix of a string literal.
+    is_started: bool,
+    /// The number of bytes on the current line of plain hex.
+    column: usize,
+    /// Whether the last byte of a string literal was written as a hex escape sequence.
+    after_hex_escape: bool,
+}
+
+impl StreamState {
+    pub(crate) fn is_started(&self) -> bool {
+        self.is_started
+    }
+}
+
+/// Renders the output formats that are not hex dumps.
+pub(crate) struct ExportPrinter<'a, Writer: Write> {
+    writer: &'a mut Writer,
+    format: OutputFormat,
+    bytes_per_line: Option<usize>,
+    variable_name: &'a str,
+}
+
+impl<'a, Writer: Write> ExportPrinter<'a, Writer> {
+    pub(crate) fn new(
+        writer: &'a mut Writer,
+        format: OutputFormat,
+        bytes_per_line: Option<usize>,
+        variable_name: &'a str,
+    ) -> Self {
+        ExportPrinter {
+            writer,
+            format,
+            bytes_per_line,
+            variable_name,
+        }
+    }
+
+    pub(crate) fn print_all<Reader: Read>(&mut self, reader: Reader) -> io::Result<()> {
+        match self.format {
+            OutputFormat::Dump
+            | OutputFormat::Json
+            | OutputFormat::Ndjson
+            | OutputFormat::Html
+            | OutputFormat::HtmlFragment => {
+                unreachable!("dumps are rendered by `Printer`")
+            }
+            OutputFormat::PlainHex
+            | OutputFormat::ShellString
+            | OutputFormat::CString
+            | OutputFormat::PythonString
+            | OutputFormat::RustString => self.print_streamed(reader)?,
+            OutputFormat::CArray
+            | OutputFormat::RustArray
+            | OutputFormat::PythonArray
+            | OutputFormat::GoArray => self.print_array(reader)?,
+        }
+        self.writer.flush()
+    }
+
+    fn print_streamed<Reader: Read>(&mut self, mut reader: Reader) -> io::Result<()> {
+        let mut state = StreamState::default();
+        let mut buf = [0; 1024];
+        loop {
+            match reader.read(&mut buf)? {
+                0 => break,
+                n => self.push(&mut state, &buf[..n])?,
+            }
+        }
+        self.end(&mut state)
+    }
+
+    /// Writes `data` as the continuation of the output in `state`, for a format that
+    /// [`OutputFormat::is_streamed`].
+    pub(crate) fn push(&mut self, state: &mut StreamState, data: &[u8]) -> io::Result<()> {
+        if !state.is_started {
+            if let Some((prefix, _, _)) = self.string_syntax() {
+                self.writer.write_all(prefix.as_bytes())?;
+            }
+            state.is_started = true;
+        }
+        if self.format == OutputFormat::PlainHex {
+            self.push_plain_hex(state, data)
+        } else {
+            self.push_string(state, data)
+        }
+    }
+
+    /// Ends the output in `state`, which can start another one afterwards.
+    pub(crate) fn end(&mut self, state: &mut StreamState) -> io::Result<()> {
+        if !state.is_started {
+            self.push(state, &[])?;
+        }
+        match self.string_syntax() {
+            Some((_, suffix, _)) => {
+                self.writer.write_all(suffix.as_bytes())?;
+                self.writer.write_all(b"\n")?;
+            }
+            None if state.column > 0 => self.writer.write_all(b"\n")?,
+            None => {}
+        }
+        *state = StreamState::default();
+        Ok(())
+    }
+
+    fn push_plain_hex(&mut self, state: &mut StreamState, data: &[u8]) -> io::Result<()> {
+        let bytes_per_line = self.bytes_per_line.unwrap_or(PLAIN_HEX_BYTES_PER_LINE);
+        for byte in data {
+            if bytes_per_line != 0 && state.column == bytes_per_line {
+                self.writer.write_all(b"\n")?;
+                state.column = 0;
+            }
+            write!(self.writer, "{byte:02x}")?;
+            state.column += 1;
+        }
+        Ok(())
+    }
+
+    /// The prefix, the suffix and the quote character of the string literal formats.
+    fn string_syntax(&self) -> Option<(&'static str, &'static str, u8)> {
+        match self.format {
+            OutputFormat::ShellString => Some(("$'", "'", b'\'')),
+            OutputFormat::CString => Some(("\"", "\"", b'"')),
+            OutputFormat::PythonString => Some(("b'", "'", b'\'')),
+            OutputFormat::RustString => Some(("b\"", "\"", b'"')),
+            _ => None,
+        }
+    }
+
+    /// Writes `data` as part of a single string literal. Printable ASCII characters are written as-is,
+    /// everything else is escaped. In C, a hex escape sequence continues for as long as there are
+    /// hex digits, so the literal has to be split if a hex digit follows one. bash cuts a shell
+    /// string at an escaped NUL byte, so it fails instead of writing a different string.
+    fn push_string(&mut self, state: &mut StreamState, data: &[u8]) -> io::Result<()> {
+        let Some((_, _, quote)) = self.string_syntax() else {
+            unreachable!("not a string format")
+        };
+        for &byte in data {
+            if byte == 0 && self.format == OutputFormat::ShellString {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "a shell string can't contain NUL bytes, use another output format",
+                ));
+            }
+            if self.format == OutputFormat::CString
+                && state.after_hex_escape
+                && byte.is_ascii_hexdigit()
+            {
+                self.writer.write_all(b"\"\"")?;
+            }
+            state.after_hex_escape = false;
+            match byte {
+                b'\n' => self.writer.write_all(b"\\n")?,
+                b'\r' => self.writer.write_all(b"\\r")?,
+                b'\t' => self.writer.write_all(b"\\t")?,
+                b'\\' => self.writer.write_all(b"\\\\")?,
+                b if b == quote => self.writer.write_all(&[b'\\', b])?,
+                b if b == b' ' || b.is_ascii_graphic() => self.writer.write_all(&[b])?,
+                b => {
+                    write!(self.writer, "\\x{b:02x}")?;
+                    state.after_hex_escape = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn print_array<Reader: Read>(&mut self, mut reader: Reader) -> io::Result<()> {
+        // the Rust type includes the length, so everything has to be read upfront
+        let mut data = vec![];
+        reader.read_to_end(&mut data)?;
+        let len = data.len();
+
+        let name = variable_name(self.variable_name);
+        let (indent, trailing_comma) = match self.format {
+            OutputFormat::CArray => {
+                writeln!(self.writer, "const uint8_t {name}[] = {{")?;
+                ("  ", false)
+            }
+            OutputFormat::RustArray => {
+                let name = name.to_uppercase();
+                writeln!(self.writer, "const {name}: &[u8; {len}] = &[")?;
+                ("    ", true)
+            }
+            OutputFormat::PythonArray => {
+                writeln!(self.writer, "{name} = bytes([")?;
+                ("    ", true)
+            }
+            OutputFormat::GoArray => {
+                writeln!(self.writer, "var {name} = []byte{{")?;
+                ("\t", true)
+            }
+            _ => unreachable!("not an array format"),
+        };
+
+        let bytes_per_line = match self.bytes_per_line.unwrap_or(ARRAY_BYTES_PER_LINE) {
+            0 => len.max(1),
+            n => n,
+        };
+        let num_lines = data.chunks(bytes_per_line).len();
+        for (i, line) in data.chunks(bytes_per_line).enumerate() {
+            let line: Vec<_> = line.iter().map(|b| format!("0x{b:02x}")).collect();
+            let comma = if trailing_comma || i + 1 < num_lines {
+                ","
+            } else {
+                ""
+            };
+            writeln!(self.writer, "{indent}{}{comma}", line.join(", "))?;
+        }
+
+        match self.format {
+            OutputFormat::CArray => {
+                writeln!(self.writer, "}};")?;
+                writeln!(self.writer, "const size_t {name}_len = {len};")
+            }
+            OutputFormat::RustArray => writeln!(self.writer, "];"),
+            OutputFormat::PythonArray => writeln!(self.writer, "])"),
+            OutputFormat::GoArray => writeln!(self.writer, "}}"),
+            _ => unreachable!("not an array format"),
+        }
+    }
+}
+
+/// Turns `name` into a valid identifier by replacing everything but ASCII letters, digits and
+/// underscores, e.g. `hello.bin` becomes `hello_bin`.
+fn variable_name(name: &str) -> String {
+    let mut identifier: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !identifier.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        identifier.insert(0, '_');
+    }
+    identifier
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(format: OutputFormat, bytes_per_line: Option<usize>, input: &[u8]) -> String {
+        let mut output = vec![];
+        ExportPrinter::new(&mut output, format, bytes_per_line, "png.header")
+            .print_all(input)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR";
+
+    #[test]
+    fn plain_hex() {
+        assert_eq!(export(OutputFormat::PlainHex, None, b""), "");
+        assert_eq!(export(OutputFormat::PlainHex, None, b"abc"), "616263\n");
+        assert_eq!(
+            export(OutputFormat::PlainHex, None, &[0xff; 31]),
+            format!("{}\nff\n", "ff".repeat(30))
+        );
+        assert_eq!(
+            export(OutputFormat::PlainHex, Some(4), PNG_HEADER),
+            "89504e47\n0d0a1a0a\n0000000d\n49484452\n"
+        );
+        assert_eq!(
+            export(OutputFormat::PlainHex, Some(0), &[0; 40]),
+            format!("{}\n", "00".repeat(40))
+        );
+    }
+
+    #[test]
+    fn shell_string() {
+        assert_eq!(
+            export(OutputFormat::ShellString, None, &PNG_HEADER[..8]),
+            "$'\\x89PNG\\r\\n\\x1a\\n'\n"
+        );
+        assert_eq!(
+            export(OutputFormat::ShellString, None, b"it's a \"\\\""),
+            "$'it\\'s a \"\\\\\"'\n"
+        );
+        assert_eq!(export(OutputFormat::ShellString, None, b""), "$''\n");
+    }
+
+    #[test]
+    fn shell_string_rejects_nul_bytes() {
+        let mut output = vec![];
+        let err = ExportPrinter::new(&mut output, OutputFormat::ShellString, None, "data")
+            .print_all(PNG_HEADER)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "a shell string can't contain NUL bytes, use another output format"
+        );
+    }
+
+    #[test]
+    fn c_string() {
+        assert_eq!(
+            export(OutputFormat::CString, None, PNG_HEADER),
+            "\"\\x89PNG\\r\\n\\x1a\\n\\x00\\x00\\x00\\rIHDR\"\n"
+        );
+        // `\x00abc` would be a single escape sequence in C
+        assert_eq!(
+            export(OutputFormat::CString, None, b"\x00abc\x00g\"'"),
+            "\"\\x00\"\"abc\\x00g\\\"'\"\n"
+        );
+    }
+
+    #[test]
+    fn python_string() {
+        assert_eq!(
+            export(OutputFormat::PythonString, None, PNG_HEADER),
+            "b'\\x89PNG\\r\\n\\x1a\\n\\x00\\x00\\x00\\rIHDR'\n"
+        );
+        assert_eq!(
+            export(OutputFormat::PythonString, None, b"\x00abc\"'"),
+            "b'\\x00abc\"\\''\n"
+        );
+    }
+
+    #[test]
+    fn rust_string() {
+        assert_eq!(
+            export(OutputFormat::RustString, None, PNG_HEADER),
+            "b\"\\x89PNG\\r\\n\\x1a\\n\\x00\\x00\\x00\\rIHDR\"\n"
+        );
+        assert_eq!(
+            export(OutputFormat::RustString, None, b"\x00abc\"'\x7f"),
+            "b\"\\x00abc\\\"'\\x7f\"\n"
+        );
+    }
+
+    #[test]
+    fn c_array() {
+        assert_eq!(
+            export(OutputFormat::CArray, None, PNG_HEADER),
+            "\
+const uint8_t png_header[] = {
+  0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d,
+  0x49, 0x48, 0x44, 0x52
+};
+const size_t png_header_len = 16;
+"
+        );
+        assert_eq!(
+            export(OutputFormat::CArray, None, b""),
+            "\
+const uint8_t png_header[] = {
+};
+const size_t png_header_len = 0;
+"
+        );
+    }
+
+    #[test]
+    fn rust_array() {
+        assert_eq!(
+            export(OutputFormat::RustArray, Some(8), PNG_HEADER),
+            "\
+const PNG_HEADER: &[u8; 16] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a,
+    0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+];
+"
+        );
+    }
+
+    #[test]
+    fn python_array() {
+        assert_eq!(
+            export(OutputFormat::PythonArray, Some(0), PNG_HEADER),
+            "\
+png_header = bytes([
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+])
+"
+        );
+    }
+
+    #[test]
+    fn go_array() {
+        assert_eq!(
+            export(OutputFormat::GoArray, Some(5), &PNG_HEADER[..8]),
+            "\
+var png_header = []byte{
+\t0x89, 0x50, 0x4e, 0x47, 0x0d,
+\t0x0a, 0x1a, 0x0a,
+}
+"
+        );
+    }
+
+    #[test]
+    fn variable_names() {
+        assert_eq!(variable_name("hello.bin"), "hello_bin");
+        assert_eq!(variable_name("my-data"), "my_data");
+        assert_eq!(variable_name("_private"), "_private");
+        assert_eq!(variable_name("2nd"), "_2nd");
+        assert_eq!(variable_name(""), "_");
+    }
+}
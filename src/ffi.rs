@@ -0,0 +1,234 @@
+//! A C API for the printer, with the `ffi` feature. Build the library for C with
+//! `cargo rustc --release --lib --features ffi --crate-type cdylib` (or `staticlib`) and include
+//! `include/hexyl.h`, which is generated from this module with `cbindgen`.
+//!
+//! The dump is written to a callback, so that the caller can collect it in any way:
+//!
+//! ```c
+//! static int write_stdout(void *user_data, const uint8_t *bytes, size_t len) {
+//!     return fwrite(bytes, 1, len, stdout) == len ? 0 : -1;
+//! }
+//!
+//! HexylConfig config;
+//! hexyl_config_default(&config);
+//! config.panels = 1;
+//! HexylPrinter *printer = hexyl_printer_new(&config, write_stdout, NULL);
+//! hexyl_printer_write(printer, (const uint8_t *)"spam", 4);
+//! hexyl_printer_finish(printer);
+//! hexyl_printer_free(printer);
+//! ```
+
+use std::ffi::{c_int, c_void};
+use std::io::{self, Write};
+use std::slice;
+
+use crate::{
+    Base, BorderStyle, CharacterTable, Endianness, Printer, PrinterBuilder, PrinterConfig,
+};
+
+pub const HEXYL_BASE_BINARY: u8 = 0;
+pub const HEXYL_BASE_OCTAL: u8 = 1;
+pub const HEXYL_BASE_DECIMAL: u8 = 2;
+pub const HEXYL_BASE_HEXADECIMAL: u8 = 3;
+
+pub const HEXYL_ENDIANNESS_LITTLE: u8 = 0;
+pub const HEXYL_ENDIANNESS_BIG: u8 = 1;
+
+pub const HEXYL_CHARACTER_TABLE_DEFAULT: u8 = 0;
+pub const HEXYL_CHARACTER_TABLE_ASCII: u8 = 1;
+pub const HEXYL_CHARACTER_TABLE_CP1047: u8 = 2;
+pub const HEXYL_CHARACTER_TABLE_CP437: u8 = 3;
+
+pub const HEXYL_BORDER_STYLE_UNICODE: u8 = 0;
+pub const HEXYL_BORDER_STYLE_ASCII: u8 = 1;
+pub const HEXYL_BORDER_STYLE_NONE: u8 = 2;
+
+/// The settings of a printer, see `PrinterConfig`. The enums are one of the `HEXYL_*`
+/// constants.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct HexylConfig {
+    pub panels: u64,
+    pub group_size: u8,
+    pub base: u8,
+    pub endianness: u8,
+    pub character_table: u8,
+    pub border_style: u8,
+    pub show_color: bool,
+    pub show_char_panel: bool,
+    pub show_position_panel: bool,
+    pub squeeze: bool,
+}
+
+impl HexylConfig {
+    fn to_config(self) -> Option<PrinterConfig> {
+        let base = match self.base {
+            HEXYL_BASE_BINARY => Base::Binary,
+            HEXYL_BASE_OCTAL => Base::Octal,
+            HEXYL_BASE_DECIMAL => Base::Decimal,
+            HEXYL_BASE_HEXADECIMAL => Base::Hexadecimal,
+            _ => return None,
+        };
+        let endianness = match self.endianness {
+            HEXYL_ENDIANNESS_LITTLE => Endianness::Little,
+            HEXYL_ENDIANNESS_BIG => Endianness::Big,
+            _ => return None,
+        };
+        let character_table = match self.character_table {
+            HEXYL_CHARACTER_TABLE_DEFAULT => CharacterTable::Default,
+            HEXYL_CHARACTER_TABLE_ASCII => CharacterTable::Ascii,
+            HEXYL_CHARACTER_TABLE_CP1047 => CharacterTable::CP1047,
+            HEXYL_CHARACTER_TABLE_CP437 => CharacterTable::CP437,
+            _ => return None,
+        };
+        let border_style = match self.border_style {
+            HEXYL_BORDER_STYLE_UNICODE => BorderStyle::Unicode,
+            HEXYL_BORDER_STYLE_ASCII => BorderStyle::Ascii,
+            HEXYL_BORDER_STYLE_NONE => BorderStyle::None,
+            _ => return None,
+        };
+        Some(PrinterConfig {
+            panels: self.panels,
+            group_size: self.group_size,
+            base,
+            endianness,
+            character_table,
+            border_style,
+            show_color: self.show_color,
+            show_char_panel: self.show_char_panel,
+            show_position_panel: self.show_position_panel,
+            squeeze: self.squeeze,
+        })
+    }
+}
+
+/// Receives the output of a printer. It returns 0 if all `len` bytes were written, anything
+/// else fails the call of the printer that wrote them.
+pub type HexylWriteCallback =
+    unsafe extern "C" fn(user_data: *mut c_void, bytes: *const u8, len: usize) -> c_int;
+
+/// Writes into a [`HexylWriteCallback`].
+struct CallbackWriter {
+    callback: HexylWriteCallback,
+    user_data: *mut c_void,
+}
+
+impl Write for CallbackWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // SAFETY: the caller of `hexyl_printer_new` guarantees that the callback can be called
+        // with `user_data`
+        match unsafe { (self.callback)(self.user_data, buf.as_ptr(), buf.len()) } {
+            0 => Ok(buf.len()),
+            _ => Err(io::Error::other("the write callback failed")),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A printer that writes a dump into a callback.
+pub struct HexylPrinter(Printer<CallbackWriter>);
+
+/// Sets `config` to the defaults of the printer.
+///
+/// # Safety
+///
+/// `config` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn hexyl_config_default(config: *mut HexylConfig) {
+    let defaults = PrinterConfig::default();
+    *config = HexylConfig {
+        panels: defaults.panels,
+        group_size: defaults.group_size,
+        base: HEXYL_BASE_HEXADECIMAL,
+        endianness: HEXYL_ENDIANNESS_BIG,
+        character_table: HEXYL_CHARACTER_TABLE_DEFAULT,
+        border_style: HEXYL_BORDER_STYLE_UNICODE,
+        show_color: defaults.show_color,
+        show_char_panel: defaults.show_char_panel,
+        show_position_panel: defaults.show_position_panel,
+        squeeze: defaults.squeeze,
+    };
+}
+
+/// Creates a printer that writes into `callback`, which gets `user_data` with every call. A null
+/// `config` uses the defaults. Returns null if the configuration is invalid.
+///
+/// # Safety
+///
+/// `config` must be null or valid for reads. `callback` must be callable with `user_data` until
+/// the printer is freed.
+#[no_mangle]
+pub unsafe extern "C" fn hexyl_printer_new(
+    config: *const HexylConfig,
+    callback: HexylWriteCallback,
+    user_data: *mut c_void,
+) -> *mut HexylPrinter {
+    let config = match config.as_ref() {
+        Some(config) => match config.to_config() {
+            Some(config) => config,
+            None => return std::ptr::null_mut(),
+        },
+        None => PrinterConfig::default(),
+    };
+    let writer = CallbackWriter {
+        callback,
+        user_data,
+    };
+    match PrinterBuilder::from_config(writer, config).try_build() {
+        Ok(printer) => Box::into_raw(Box::new(HexylPrinter(printer))),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Prints `len` bytes of the input, which are dumped as soon as a row is complete. Returns 0, or
+/// -1 if the write callback failed.
+///
+/// # Safety
+///
+/// `printer` must come from `hexyl_printer_new`, and `bytes` must be valid for reads of `len`
+/// bytes.
+#[no_mangle]
+pub unsafe extern "C" fn hexyl_printer_write(
+    printer: *mut HexylPrinter,
+    bytes: *const u8,
+    len: usize,
+) -> c_int {
+    let bytes = if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(bytes, len)
+    };
+    match (*printer).0.write_all(bytes) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// Prints the rest of the dump. Bytes that are written afterwards start a new dump. Returns 0,
+/// or -1 if the write callback failed.
+///
+/// # Safety
+///
+/// `printer` must come from `hexyl_printer_new`.
+#[no_mangle]
+pub unsafe extern "C" fn hexyl_printer_finish(printer: *mut HexylPrinter) -> c_int {
+    match (*printer).0.finish() {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// Frees a printer without finishing its dump. Null is ignored.
+///
+/// # Safety
+///
+/// `printer` must be null or come from `hexyl_printer_new`, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn hexyl_printer_free(printer: *mut HexylPrinter) {
+    if !printer.is_null() {
+        drop(Box::from_raw(printer));
+    }
+}
@@ -0,0 +1,160 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Information about the input, which is shown in a row inside the top border of hexyl's own
+/// layout, see [`PrinterBuilder::header`]. Every field is optional and left out if it is `None`.
+///
+/// ```
+/// use hexyl::{DumpHeader, PrinterBuilder};
+///
+/// let mut output = vec![];
+/// let mut printer = PrinterBuilder::new(&mut output)
+///     .num_panels(4)
+///     .header(DumpHeader {
+///         name: Some("data.bin".into()),
+///         size: Some(4404019),
+///         modified: Some(1714564800),
+///         range: Some(0x100..0x200),
+///     })
+///     .build();
+/// printer.print_all(&[0; 256][..]).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains(
+///     "data.bin, modified 2024-05-01 12:00:00 UTC, bytes 0x100–0x1ff of 4.2 MiB (4404019 bytes)"
+/// ));
+/// ```
+///
+/// [`PrinterBuilder::header`]: crate::PrinterBuilder::header
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DumpHeader {
+    /// The name of the input, e.g. the path of a file.
+    pub name: Option<String>,
+    /// The size of the whole input in bytes.
+    pub size: Option<u64>,
+    /// The time of the last modification of the input, in seconds since the Unix epoch.
+    pub modified: Option<i64>,
+    /// The positions of the dumped bytes in the input.
+    pub range: Option<Range<u64>>,
+}
+
+impl DumpHeader {
+    /// The text of the header row.
+    pub(crate) fn text(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(name) = &self.name {
+            parts.push(name.clone());
+        }
+        if let Some(modified) = self.modified {
+            parts.push(format!("modified {}", format_time(modified)));
+        }
+        let size = self.size.map(format_size);
+        match (&self.range, size) {
+            (Some(range), size) => {
+                let mut part = if range.is_empty() {
+                    String::from("no bytes")
+                } else {
+                    format!("bytes {:#x}–{:#x}", range.start, range.end - 1)
+                };
+                if let Some(size) = size {
+                    part += &format!(" of {size}");
+                }
+                parts.push(part);
+            }
+            (None, Some(size)) => parts.push(size),
+            (None, None) => {}
+        }
+        parts.join(", ")
+    }
+}
+
+/// Formats `size` with a binary prefix and as the exact number of bytes, e.g. `4.2 MiB (4404019
+/// bytes)`.
+fn format_size(size: u64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    if size < 1024 {
+        return format!("{size} bytes");
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {} ({size} bytes)", UNITS[unit])
+}
+
+/// Formats `secs` since the Unix epoch as a date and time in UTC.
+fn format_time(secs: i64) -> String {
+    // the days since the epoch to a date of the proleptic Gregorian calendar, from
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = secs.div_euclid(86400) + 719_468;
+    let secs_of_day = secs.rem_euclid(86400);
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size() {
+        assert_eq!(format_size(0), "0 bytes");
+        assert_eq!(format_size(1023), "1023 bytes");
+        assert_eq!(format_size(1024), "1.0 KiB (1024 bytes)");
+        assert_eq!(format_size(4404019), "4.2 MiB (4404019 bytes)");
+        assert_eq!(
+            format_size(u64::MAX),
+            "16.0 EiB (18446744073709551615 bytes)"
+        );
+    }
+
+    #[test]
+    fn time() {
+        assert_eq!(format_time(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_time(951_782_400), "2000-02-29 00:00:00 UTC");
+        assert_eq!(format_time(1_714_564_800), "2024-05-01 12:00:00 UTC");
+        assert_eq!(format_time(-1), "1969-12-31 23:59:59 UTC");
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(DumpHeader::default().text(), "");
+        let header = DumpHeader {
+            name: Some("ascii".into()),
+            size: Some(16),
+            modified: None,
+            range: None,
+        };
+        assert_eq!(header.text(), "ascii, 16 bytes");
+        let header = DumpHeader {
+            range: Some(2..6),
+            ..header
+        };
+        assert_eq!(header.text(), "ascii, bytes 0x2–0x5 of 16 bytes");
+        let header = DumpHeader {
+            range: Some(16..16),
+            ..header
+        };
+        assert_eq!(header.text(), "ascii, no bytes of 16 bytes");
+    }
+}
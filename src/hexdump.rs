@@ -0,0 +1,256 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::io::{self, Write};
+
+use crate::{Base, BorderStyle, CharacterTable, Endianness, PrinterBuilder};
+
+/// Formats a byte slice as a hexyl dump with `{}` or `{:?}`, e.g. for logging:
+///
+/// ```
+/// use hexyl::HexDump;
+///
+/// let dump = HexDump::new(b"spam").panels(1).color(false);
+/// assert_eq!(
+///     dump.to_string(),
+///     "\
+/// ┌────────┬─────────────────────────┬────────┐
+/// │00000000│ 73 70 61 6d             │spam    │
+/// └────────┴─────────────────────────┴────────┘
+/// "
+/// );
+/// ```
+#[derive(Copy, Clone)]
+pub struct HexDump<'a> {
+    bytes: &'a [u8],
+    show_color: bool,
+    show_char_panel: bool,
+    show_position_panel: bool,
+    border_style: BorderStyle,
+    use_squeeze: bool,
+    panels: u64,
+    group_size: u8,
+    base: Base,
+    endianness: Endianness,
+    character_table: CharacterTable,
+    display_offset: u64,
+}
+
+impl<'a> HexDump<'a> {
+    /// A dump of `bytes` with the same defaults as [`PrinterBuilder`].
+    pub fn new(bytes: &'a [u8]) -> Self {
+        HexDump {
+            bytes,
+            show_color: true,
+            show_char_panel: true,
+            show_position_panel: true,
+            border_style: BorderStyle::Unicode,
+            use_squeeze: true,
+            panels: 2,
+            group_size: 1,
+            base: Base::Hexadecimal,
+            endianness: Endianness::Big,
+            character_table: CharacterTable::Default,
+            display_offset: 0,
+        }
+    }
+
+    pub fn color(mut self, show_color: bool) -> Self {
+        self.show_color = show_color;
+        self
+    }
+
+    pub fn char_panel(mut self, show_char_panel: bool) -> Self {
+        self.show_char_panel = show_char_panel;
+        self
+    }
+
+    pub fn position_panel(mut self, show_position_panel: bool) -> Self {
+        self.show_position_panel = show_position_panel;
+        self
+    }
+
+    pub fn border_style(mut self, border_style: BorderStyle) -> Self {
+        self.border_style = border_style;
+        self
+    }
+
+    pub fn squeeze(mut self, enable: bool) -> Self {
+        self.use_squeeze = enable;
+        self
+    }
+
+    pub fn panels(mut self, num: u64) -> Self {
+        self.panels = num;
+        self
+    }
+
+    pub fn group_size(mut self, num: u8) -> Self {
+        self.group_size = num;
+        self
+    }
+
+    pub fn base(mut self, base: Base) -> Self {
+        self.base = base;
+        self
+    }
+
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
+    pub fn character_table(mut self, character_table: CharacterTable) -> Self {
+        self.character_table = character_table;
+        self
+    }
+
+    pub fn display_offset(mut self, display_offset: u64) -> Self {
+        self.display_offset = display_offset;
+        self
+    }
+}
+
+impl fmt::Display for HexDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut writer = FmtWriter::new(f);
+        let mut printer = PrinterBuilder::new(&mut writer)
+            .show_color(self.show_color)
+            .show_char_panel(self.show_char_panel)
+            .show_position_panel(self.show_position_panel)
+            .with_border_style(self.border_style)
+            .enable_squeezing(self.use_squeeze)
+            .num_panels(self.panels)
+            .group_size(self.group_size)
+            .with_base(self.base)
+            .endianness(self.endianness)
+            .character_table(self.character_table)
+            .try_build()
+            .map_err(|_| fmt::Error)?;
+        printer.display_offset(self.display_offset);
+        printer
+            .print_all(self.bytes)
+            .map(|_| ())
+            .map_err(|_| fmt::Error)
+    }
+}
+
+/// Same as [`fmt::Display`], so that types containing bytes can derive a readable `Debug`.
+impl fmt::Debug for HexDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Lets the printer write into a [`fmt::Write`]. A UTF-8 sequence that is split between two
+/// writes is kept until it is complete.
+pub(crate) struct FmtWriter<W: fmt::Write> {
+    inner: W,
+    incomplete: Vec<u8>,
+}
+
+impl<W: fmt::Write> FmtWriter<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        FmtWriter {
+            inner,
+            incomplete: vec![],
+        }
+    }
+
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.inner
+            .write_str(s)
+            .map_err(|_| io::Error::other("formatter error"))
+    }
+
+    fn write_bytes(&mut self, buf: &[u8]) -> io::Result<()> {
+        let mut bytes = buf;
+        let joined;
+        if !self.incomplete.is_empty() {
+            self.incomplete.extend_from_slice(buf);
+            joined = core::mem::take(&mut self.incomplete);
+            bytes = &joined;
+        }
+        match core::str::from_utf8(bytes) {
+            Ok(s) => self.write_str(s),
+            Err(e) if e.error_len().is_none() => {
+                let (valid, rest) = bytes.split_at(e.valid_up_to());
+                self.write_str(core::str::from_utf8(valid).unwrap())?;
+                self.incomplete = rest.to_vec();
+                Ok(())
+            }
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: fmt::Write> Write for FmtWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_bytes(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<W: fmt::Write> Write for FmtWriter<W> {
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.write_bytes(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let input = b"spamspamspamspamspam";
+        let expected = "\
+┌────────┬─────────────────────────┬─────────────────────────┬────────┬────────┐
+│deadbeef│ 73 70 61 6d 73 70 61 6d ┊ 73 70 61 6d 73 70 61 6d │spamspam┊spamspam│
+│deadbeff│ 73 70 61 6d             ┊                         │spam    ┊        │
+└────────┴─────────────────────────┴─────────────────────────┴────────┴────────┘
+";
+        let dump = HexDump::new(input).color(false).display_offset(0xdeadbeef);
+        assert_eq!(format!("{dump}"), expected);
+    }
+
+    #[test]
+    fn debug() {
+        #[derive(Debug)]
+        struct Packet<'a> {
+            payload: HexDump<'a>,
+        }
+
+        let packet = Packet {
+            payload: HexDump::new(b"\x00")
+                .border_style(BorderStyle::None)
+                .color(false),
+        };
+        assert_eq!(
+            format!("{packet:?}"),
+            format!("Packet {{ payload: {} }}", packet.payload)
+        );
+        assert!(format!("{packet:?}").contains(" 00000000  00 "));
+    }
+
+    #[test]
+    fn split_utf8_sequence() {
+        let mut output = String::new();
+        let mut writer = FmtWriter::new(&mut output);
+        let bytes = "┊⋄".as_bytes();
+        writer.write_all(&bytes[..1]).unwrap();
+        writer.write_all(&bytes[1..4]).unwrap();
+        writer.write_all(&bytes[4..]).unwrap();
+        assert_eq!(output, "┊⋄");
+    }
+}
@@ -0,0 +1,59 @@
+use alloc::string::String;
+
+use crate::io::{self, Write};
+
+use crate::ColorType;
+
+/// The categories that have a CSS class, in the order of the stylesheet.
+const CLASSES: [ColorType; 7] = [
+    ColorType::Offset,
+    ColorType::Null,
+    ColorType::AsciiPrintable,
+    ColorType::AsciiWhitespace,
+    ColorType::AsciiOther,
+    ColorType::NonAscii,
+    ColorType::Unreadable,
+];
+
+/// Writes everything of a standalone HTML document that comes before the dump, including the
+/// default stylesheet.
+pub(crate) fn write_document_start<Writer: Write>(writer: &mut Writer) -> io::Result<()> {
+    writer.write_all(
+        b"<!DOCTYPE html>\n\
+          <html>\n\
+          <head>\n\
+          <meta charset=\"utf-8\">\n\
+          <title>hexyl</title>\n\
+          <style>\n\
+          pre.hexyl { background: #1e1e1e; color: #d0d0d0; padding: 1em; }\n\
+          pre.hexyl a { color: inherit; text-decoration: none; }\n",
+    )?;
+    for color in CLASSES {
+        writeln!(
+            writer,
+            "pre.hexyl .{} {{ color: {}; }}",
+            color.css_class().unwrap(),
+            color.css_color().unwrap()
+        )?;
+    }
+    writer.write_all(b"</style>\n</head>\n<body>\n")
+}
+
+pub(crate) fn write_document_end<Writer: Write>(writer: &mut Writer) -> io::Result<()> {
+    writer.write_all(b"</body>\n</html>\n")
+}
+
+/// Escapes the characters that have a meaning in HTML.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -21,7 +21,7 @@ impl<'a> Seek for Input<'a> {
         where
             R: Read,
         {
-            let cant_seek_abs_err = || Err(io::Error::new(io::ErrorKind::Other, err_desc));
+            let cant_seek_abs_err = || Err(io::Error::other(err_desc));
 
             let offset = match pos {
                 SeekFrom::Current(o) => u64::try_from(o).or_else(|_e| cant_seek_abs_err())?,
@@ -61,4 +61,27 @@ impl<'a> Input<'a> {
             Input::Stdin(stdin) => Box::new(stdin),
         }
     }
+
+    /// The metadata of the file, or of what STDIN refers to, e.g. a file that is redirected to
+    /// it.
+    pub fn metadata(&self) -> io::Result<fs::Metadata> {
+        match self {
+            Input::File(file) => file.metadata(),
+            #[cfg(unix)]
+            Input::Stdin(stdin) => {
+                use std::os::fd::AsFd;
+                fs::File::from(stdin.as_fd().try_clone_to_owned()?).metadata()
+            }
+            #[cfg(windows)]
+            Input::Stdin(stdin) => {
+                use std::os::windows::io::AsHandle;
+                fs::File::from(stdin.as_handle().try_clone_to_owned()?).metadata()
+            }
+            #[cfg(not(any(unix, windows)))]
+            Input::Stdin(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the metadata of STDIN is not supported on this platform",
+            )),
+        }
+    }
 }
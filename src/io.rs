@@ -0,0 +1,223 @@
+//! The I/O traits that the printer works with. With the `std` feature, these are the ones of
+//! [`std::io`]. Without it, this module provides minimal replacements, so that a dump can be
+//! written to anything that implements [`Write`], e.g. a UART.
+
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, Read, Result, Write};
+
+#[cfg(not(feature = "std"))]
+pub use self::no_std::{Error, ErrorKind, Read, Result, Write};
+
+/// An error while reading the input of a dump, at the position of the first byte that could not
+/// be read. It is the inner error of the [`Error`] that the printer returns.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct ReadError {
+    offset: u64,
+    source: Error,
+}
+
+#[cfg(feature = "std")]
+impl ReadError {
+    /// The position of the unreadable byte, including the display offset.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The error of the reader.
+    pub fn io_error(&self) -> &Error {
+        &self.source
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for ReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "failed to read the input at offset {:#x}: {}",
+            self.offset, self.source
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReadError {}
+
+/// Adds the `offset` at which reading failed to `error`.
+#[cfg(feature = "std")]
+pub(crate) fn read_error(offset: u64, error: Error) -> Error {
+    Error::new(
+        error.kind(),
+        ReadError {
+            offset,
+            source: error,
+        },
+    )
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn read_error(_offset: u64, error: Error) -> Error {
+    error
+}
+
+/// Reads the input of a dump. Interrupted reads are retried, and other errors get the offset at
+/// which they occurred.
+pub(crate) struct InputReader<R: Read> {
+    inner: R,
+    offset: u64,
+}
+
+impl<R: Read> InputReader<R> {
+    /// A reader of `inner`, whose first byte is at `offset`.
+    pub(crate) fn new(inner: R, offset: u64) -> Self {
+        InputReader { inner, offset }
+    }
+
+    pub(crate) fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for InputReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        loop {
+            match self.inner.read(buf) {
+                Ok(n) => {
+                    self.offset += n as u64;
+                    return Ok(n);
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(read_error(self.offset, e)),
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod no_std {
+    use alloc::vec::Vec;
+    use core::fmt;
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[non_exhaustive]
+    pub enum ErrorKind {
+        InvalidData,
+        Interrupted,
+        Other,
+    }
+
+    #[derive(Debug)]
+    pub struct Error {
+        kind: ErrorKind,
+        message: &'static str,
+    }
+
+    impl Error {
+        pub fn new(kind: ErrorKind, message: &'static str) -> Error {
+            Error { kind, message }
+        }
+
+        pub fn other(message: &'static str) -> Error {
+            Error::new(ErrorKind::Other, message)
+        }
+
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.message)
+        }
+    }
+
+    /// A sink for bytes, like `std::io::Write`.
+    pub trait Write {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()>;
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        /// Makes `write!` and `writeln!` work.
+        fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<()> {
+            struct Adapter<'a, W: ?Sized> {
+                inner: &'a mut W,
+                error: Option<Error>,
+            }
+
+            impl<W: Write + ?Sized> fmt::Write for Adapter<'_, W> {
+                fn write_str(&mut self, s: &str) -> fmt::Result {
+                    self.inner.write_all(s.as_bytes()).map_err(|e| {
+                        self.error = Some(e);
+                        fmt::Error
+                    })
+                }
+            }
+
+            let mut adapter = Adapter {
+                inner: self,
+                error: None,
+            };
+            fmt::write(&mut adapter, args).map_err(|_| {
+                adapter
+                    .error
+                    .unwrap_or_else(|| Error::other("formatter error"))
+            })
+        }
+    }
+
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            (**self).write_all(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            (**self).flush()
+        }
+    }
+
+    impl Write for Vec<u8> {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            self.extend_from_slice(buf);
+            Ok(())
+        }
+    }
+
+    /// A source of bytes, like `std::io::Read`.
+    pub trait Read {
+        /// Reads into `buf` and returns the number of bytes read, which is 0 at the end.
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+        fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+            let start = buf.len();
+            let mut chunk = [0; 256];
+            loop {
+                match self.read(&mut chunk)? {
+                    0 => return Ok(buf.len() - start),
+                    n => buf.extend_from_slice(&chunk[..n]),
+                }
+            }
+        }
+    }
+
+    impl<R: Read + ?Sized> Read for &mut R {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            (**self).read(buf)
+        }
+    }
+
+    impl Read for &[u8] {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let n = buf.len().min(self.len());
+            let (head, tail) = self.split_at(n);
+            buf[..n].copy_from_slice(head);
+            *self = tail;
+            Ok(n)
+        }
+    }
+}
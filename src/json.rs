@@ -0,0 +1,204 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use crate::io::{self, Write};
+
+use crate::render::Renderer;
+use crate::{Base, Byte, CharacterTable, Endianness};
+
+/// Renders every row of the dump as a JSON object, either as elements of a JSON array or as
+/// newline-delimited JSON.
+pub(crate) struct JsonRenderer<Writer: Write> {
+    pub(crate) writer: Writer,
+    ndjson: bool,
+    base: Base,
+    group_size: u8,
+    endianness: Endianness,
+    character_table: CharacterTable,
+    offset: u64,
+    /// The bytes of the current row, `None` for unreadable bytes. Squeezed rows repeat the
+    /// previous one.
+    bytes: Vec<Option<u8>>,
+    is_first_row: bool,
+}
+
+impl<Writer: Write> JsonRenderer<Writer> {
+    pub(crate) fn new(
+        writer: Writer,
+        ndjson: bool,
+        base: Base,
+        group_size: u8,
+        endianness: Endianness,
+        character_table: CharacterTable,
+    ) -> Self {
+        JsonRenderer {
+            writer,
+            ndjson,
+            base,
+            group_size,
+            endianness,
+            character_table,
+            offset: 0,
+            bytes: vec![],
+            is_first_row: true,
+        }
+    }
+
+    /// A renderer with the same settings and state that renders into a buffer, see
+    /// [`ForkRenderer`](crate::render::ForkRenderer).
+    #[cfg(feature = "parallel")]
+    pub(crate) fn fork(&self) -> JsonRenderer<Vec<u8>> {
+        JsonRenderer {
+            writer: Vec::new(),
+            ndjson: self.ndjson,
+            base: self.base,
+            group_size: self.group_size,
+            endianness: self.endianness,
+            character_table: self.character_table,
+            offset: self.offset,
+            bytes: self.bytes.clone(),
+            is_first_row: self.is_first_row,
+        }
+    }
+
+    /// Continues with the state of `fork`, which rendered the rows before the next one.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn resume(&mut self, fork: &JsonRenderer<Vec<u8>>) {
+        self.offset = fork.offset;
+        self.bytes.clone_from(&fork.bytes);
+        self.is_first_row = fork.is_first_row;
+    }
+
+    fn write_row(&mut self, squeezed: bool) -> io::Result<()> {
+        let mut row = String::new();
+        if !self.ndjson {
+            row.push_str(if self.is_first_row { "\n  " } else { ",\n  " });
+        }
+        self.is_first_row = false;
+
+        row += &format!("{{\"offset\":{},\"bytes\":[", self.offset);
+        for (i, b) in self.bytes.iter().enumerate() {
+            if i > 0 {
+                row.push(',');
+            }
+            match b {
+                Some(b) => row += &b.to_string(),
+                None => row.push_str("null"),
+            }
+        }
+
+        row.push_str("],\"hex\":[");
+        for (i, group) in self.bytes.chunks(self.group_size as usize).enumerate() {
+            if i > 0 {
+                row.push(',');
+            }
+            let format = |&b: &Option<u8>| match b {
+                Some(b) => self.base.format_byte(b),
+                None => "?".repeat(self.base.digits() as usize),
+            };
+            let hex: String = match self.endianness {
+                Endianness::Big => group.iter().map(format).collect(),
+                Endianness::Little => group.iter().rev().map(format).collect(),
+            };
+            push_json_string(&mut row, &hex);
+        }
+
+        row.push_str("],\"chars\":");
+        let chars: String = self
+            .bytes
+            .iter()
+            .map(|&b| b.map_or('?', |b| Byte(b).as_char(self.character_table)))
+            .collect();
+        push_json_string(&mut row, &chars);
+
+        row.push_str(",\"categories\":[");
+        for (i, &b) in self.bytes.iter().enumerate() {
+            if i > 0 {
+                row.push(',');
+            }
+            let category = b.map_or("unreadable", |b| Byte(b).category().name());
+            push_json_string(&mut row, category);
+        }
+        row += &format!("],\"squeezed\":{squeezed}}}");
+
+        if self.ndjson {
+            row.push('\n');
+        }
+        self.writer.write_all(row.as_bytes())
+    }
+}
+
+impl<Writer: Write> Renderer for JsonRenderer<Writer> {
+    fn begin_dump(&mut self) -> io::Result<()> {
+        if !self.ndjson {
+            self.writer.write_all(b"[")?;
+        }
+        Ok(())
+    }
+
+    fn empty_dump(&mut self) -> io::Result<()> {
+        self.begin_dump()
+    }
+
+    fn end_dump(&mut self) -> io::Result<()> {
+        if !self.ndjson {
+            self.writer.write_all(b"\n]\n")?;
+        }
+        self.writer.flush()
+    }
+
+    fn begin_row(&mut self, offset: u64) -> io::Result<()> {
+        self.offset = offset;
+        self.bytes.clear();
+        Ok(())
+    }
+
+    fn byte(&mut self, _index: usize, byte: u8) -> io::Result<()> {
+        self.bytes.push(Some(byte));
+        Ok(())
+    }
+
+    fn unreadable_byte(&mut self, _index: usize) -> io::Result<()> {
+        self.bytes.push(None);
+        Ok(())
+    }
+
+    fn partial_row(&mut self, _len: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn char_cell(&mut self, _index: usize, _byte: u8) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn unreadable_char(&mut self, _index: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn end_row(&mut self) -> io::Result<()> {
+        self.write_row(false)
+    }
+
+    fn squeeze_marker(&mut self, offset: u64) -> io::Result<()> {
+        self.offset = offset;
+        self.write_row(true)
+    }
+}
+
+/// Appends `s` as a quoted JSON string.
+fn push_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,397 @@
+use alloc::format;
+use alloc::string::String;
+
+use crate::io::{self, Read, Write};
+
+/// The overall format of a dump.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[non_exhaustive]
+pub enum Layout {
+    /// hexyl's own format.
+    #[default]
+    Hexyl,
+
+    /// The format of 'xxd'. Squeezing follows 'xxd -a' and only applies to
+    /// lines of NULL bytes, so disable it for the output of plain 'xxd'.
+    Xxd,
+
+    /// The format of 'hexdump -C'.
+    Hexdump,
+
+    /// The format of 'od -A x -t x1z'.
+    Od,
+}
+
+/// The number of bytes per line in all of the emulated layouts.
+const BYTES_PER_LINE: usize = 16;
+
+/// Renders the layouts of other hex dump tools. These only share the squeezing and the display
+/// offset with the hexyl layout, everything else is fixed to the defaults of the respective tool.
+pub(crate) struct CompatPrinter<'a, Writer: Write> {
+    writer: &'a mut Writer,
+    layout: Layout,
+    use_squeeze: bool,
+    display_offset: u64,
+}
+
+impl<'a, Writer: Write> CompatPrinter<'a, Writer> {
+    pub(crate) fn new(
+        writer: &'a mut Writer,
+        layout: Layout,
+        use_squeeze: bool,
+        display_offset: u64,
+    ) -> Self {
+        CompatPrinter {
+            writer,
+            layout,
+            use_squeeze,
+            display_offset,
+        }
+    }
+
+    pub(crate) fn print_all<Reader: Read>(&mut self, mut reader: Reader) -> io::Result<()> {
+        let mut line = [0u8; BYTES_PER_LINE];
+        let mut prev_line = [0u8; BYTES_PER_LINE];
+        let mut offset = self.display_offset;
+        let mut is_first = true;
+        let mut star_printed = false;
+        let mut xxd_squeezer = XxdSqueezer::default();
+
+        loop {
+            let n = fill_line(&mut reader, &mut line)?;
+            if n == 0 {
+                break;
+            }
+            let line = &line[..n];
+
+            if self.layout == Layout::Xxd {
+                let text = self.format_line(offset, line);
+                let nonzero = n < BYTES_PER_LINE || line.iter().any(|&b| b != 0);
+                if self.use_squeeze {
+                    xxd_squeezer.push(self.writer, text, nonzero)?;
+                } else {
+                    self.writer.write_all(text.as_bytes())?;
+                }
+            } else if self.use_squeeze && !is_first && n == BYTES_PER_LINE && line == prev_line {
+                // incomplete lines are never squeezed
+                if !star_printed {
+                    self.writer.write_all(b"*\n")?;
+                    star_printed = true;
+                }
+            } else {
+                let text = self.format_line(offset, line);
+                self.writer.write_all(text.as_bytes())?;
+                star_printed = false;
+            }
+
+            prev_line[..n].copy_from_slice(line);
+            offset += n as u64;
+            is_first = false;
+            if n < BYTES_PER_LINE {
+                break;
+            }
+        }
+
+        match self.layout {
+            Layout::Xxd => xxd_squeezer.finish(self.writer)?,
+            Layout::Hexdump if !is_first => writeln!(self.writer, "{offset:08x}")?,
+            Layout::Od => writeln!(self.writer, "{offset:06x}")?,
+            Layout::Hexyl | Layout::Hexdump => {}
+        }
+
+        self.writer.flush()
+    }
+
+    fn format_line(&self, offset: u64, line: &[u8]) -> String {
+        let mut text = String::new();
+        match self.layout {
+            Layout::Xxd => {
+                text += &format!("{offset:08x}:");
+                for i in 0..BYTES_PER_LINE {
+                    if i % 2 == 0 {
+                        text.push(' ');
+                    }
+                    push_hex(&mut text, line.get(i));
+                }
+                text.push_str("  ");
+                push_chars(&mut text, line);
+            }
+            Layout::Hexyl => unreachable!("the hexyl layout is rendered by `Printer`"),
+            Layout::Hexdump => {
+                text += &format!("{offset:08x} ");
+                for i in 0..BYTES_PER_LINE {
+                    text.push(' ');
+                    if i == 8 {
+                        text.push(' ');
+                    }
+                    push_hex(&mut text, line.get(i));
+                }
+                text.push_str("  |");
+                push_chars(&mut text, line);
+                text.push('|');
+            }
+            Layout::Od => {
+                text += &format!("{offset:06x}");
+                for i in 0..BYTES_PER_LINE {
+                    text.push(' ');
+                    push_hex(&mut text, line.get(i));
+                }
+                text.push_str("  >");
+                push_chars(&mut text, line);
+                text.push('<');
+            }
+        }
+        text.push('\n');
+        text
+    }
+}
+
+/// Writes the byte as two hex digits, or pads with two spaces if it is missing.
+fn push_hex(text: &mut String, byte: Option<&u8>) {
+    match byte {
+        Some(b) => text.push_str(&format!("{b:02x}")),
+        None => text.push_str("  "),
+    }
+}
+
+/// Writes printable ASCII characters as-is and '.' for everything else.
+fn push_chars(text: &mut String, line: &[u8]) {
+    text.extend(line.iter().map(|&b| {
+        if b == b' ' || b.is_ascii_graphic() {
+            b as char
+        } else {
+            '.'
+        }
+    }));
+}
+
+/// Reads until `line` is full or the reader is exhausted.
+fn fill_line<Reader: Read>(reader: &mut Reader, line: &mut [u8]) -> io::Result<usize> {
+    let mut n = 0;
+    while n < line.len() {
+        match reader.read(&mut line[n..])? {
+            0 => break,
+            read => n += read,
+        }
+    }
+    Ok(n)
+}
+
+/// The autoskip of `xxd -a`: runs of lines that only consist of NULL bytes are replaced by a
+/// single `*`, but the first and the last line of the run are still printed. A run of three lines
+/// is printed as-is.
+#[derive(Default)]
+struct XxdSqueezer {
+    /// The number of consecutive lines of NULL bytes, including the current one.
+    zero_lines: usize,
+    /// The second line of the current run of NULL lines.
+    second: String,
+    /// The most recent line.
+    last: String,
+}
+
+impl XxdSqueezer {
+    fn push<Writer: Write>(
+        &mut self,
+        writer: &mut Writer,
+        line: String,
+        nonzero: bool,
+    ) -> io::Result<()> {
+        if nonzero {
+            self.flush_run(writer)?;
+            writer.write_all(line.as_bytes())?;
+            self.zero_lines = 0;
+        } else {
+            self.zero_lines += 1;
+            match self.zero_lines {
+                1 => writer.write_all(line.as_bytes())?,
+                2 => self.second.clone_from(&line),
+                _ => {}
+            }
+        }
+        self.last = line;
+        Ok(())
+    }
+
+    fn finish<Writer: Write>(&mut self, writer: &mut Writer) -> io::Result<()> {
+        if self.zero_lines > 1 {
+            // the last line of the run still has to be printed
+            self.zero_lines -= 1;
+            self.flush_run(writer)?;
+            writer.write_all(self.last.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Prints what stands in for the suppressed lines of the current run.
+    fn flush_run<Writer: Write>(&mut self, writer: &mut Writer) -> io::Result<()> {
+        match self.zero_lines {
+            0 | 1 => Ok(()),
+            2 => writer.write_all(self.second.as_bytes()),
+            _ => writer.write_all(b"*\n"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    fn assert_output(layout: Layout, use_squeeze: bool, input: &[u8], expected: &str) {
+        let mut output = vec![];
+        CompatPrinter::new(&mut output, layout, use_squeeze, 0)
+            .print_all(input)
+            .unwrap();
+        assert_eq!(std::str::from_utf8(&output).unwrap(), expected);
+    }
+
+    fn zeros_with_gap() -> Vec<u8> {
+        let mut input = vec![0; 32];
+        input.push(b'x');
+        input.extend_from_slice(&[0; 67]);
+        input
+    }
+
+    #[test]
+    fn xxd() {
+        assert_output(
+            Layout::Xxd,
+            true,
+            b"0123456789abcde\n\x00\x7f\x80\xff \t~",
+            "\
+00000000: 3031 3233 3435 3637 3839 6162 6364 650a  0123456789abcde.
+00000010: 007f 80ff 2009 7e                        .... .~
+",
+        );
+        assert_output(Layout::Xxd, true, b"", "");
+    }
+
+    #[test]
+    fn xxd_autoskip() {
+        assert_output(
+            Layout::Xxd,
+            true,
+            &zeros_with_gap(),
+            "\
+00000000: 0000 0000 0000 0000 0000 0000 0000 0000  ................
+00000010: 0000 0000 0000 0000 0000 0000 0000 0000  ................
+00000020: 7800 0000 0000 0000 0000 0000 0000 0000  x...............
+00000030: 0000 0000 0000 0000 0000 0000 0000 0000  ................
+*
+00000060: 0000 0000                                ....
+",
+        );
+        assert_output(
+            Layout::Xxd,
+            true,
+            &[0; 48],
+            "\
+00000000: 0000 0000 0000 0000 0000 0000 0000 0000  ................
+00000010: 0000 0000 0000 0000 0000 0000 0000 0000  ................
+00000020: 0000 0000 0000 0000 0000 0000 0000 0000  ................
+",
+        );
+        assert_output(
+            Layout::Xxd,
+            true,
+            &[0; 64],
+            "\
+00000000: 0000 0000 0000 0000 0000 0000 0000 0000  ................
+*
+00000030: 0000 0000 0000 0000 0000 0000 0000 0000  ................
+",
+        );
+    }
+
+    #[test]
+    fn hexdump() {
+        assert_output(
+            Layout::Hexdump,
+            true,
+            b"abc",
+            "\
+00000000  61 62 63                                          |abc|
+00000003
+",
+        );
+        assert_output(Layout::Hexdump, true, b"", "");
+    }
+
+    #[test]
+    fn hexdump_squeeze() {
+        assert_output(
+            Layout::Hexdump,
+            true,
+            &zeros_with_gap(),
+            "\
+00000000  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|
+*
+00000020  78 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |x...............|
+00000030  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|
+*
+00000060  00 00 00 00                                       |....|
+00000064
+",
+        );
+    }
+
+    #[test]
+    fn od() {
+        assert_output(
+            Layout::Od,
+            true,
+            b"abc",
+            "\
+000000 61 62 63                                         >abc<
+000003
+",
+        );
+        assert_output(Layout::Od, true, b"", "000000\n");
+    }
+
+    #[test]
+    fn od_squeeze() {
+        assert_output(
+            Layout::Od,
+            true,
+            &zeros_with_gap(),
+            "\
+000000 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  >................<
+*
+000020 78 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  >x...............<
+000030 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  >................<
+*
+000060 00 00 00 00                                      >....<
+000064
+",
+        );
+        assert_output(
+            Layout::Od,
+            false,
+            &[0; 32],
+            "\
+000000 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  >................<
+000010 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  >................<
+000020
+",
+        );
+    }
+
+    #[test]
+    fn display_offset() {
+        let mut output = vec![];
+        CompatPrinter::new(&mut output, Layout::Hexdump, true, 0x1000)
+            .print_all(io::Cursor::new(b"abc"))
+            .unwrap();
+        assert_eq!(
+            std::str::from_utf8(&output).unwrap(),
+            "\
+00001000  61 62 63                                          |abc|
+00001003
+"
+        );
+    }
+}
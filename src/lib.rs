@@ -1,11 +1,69 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "tokio")]
+pub(crate) mod async_printer;
+pub(crate) mod classify;
 pub(crate) mod colors;
+pub(crate) mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub(crate) mod header;
+pub(crate) mod hexdump;
+pub(crate) mod html;
+#[cfg(feature = "std")]
 pub(crate) mod input;
-
+pub mod io;
+pub(crate) mod json;
+pub(crate) mod layout;
+#[cfg(feature = "mmap")]
+pub(crate) mod mmap;
+pub(crate) mod render;
+#[cfg(feature = "std")]
+pub(crate) mod reverse;
+pub(crate) mod rows;
+pub(crate) mod summary;
+pub(crate) mod terminal;
+
+#[cfg(feature = "tokio")]
+pub use async_printer::AsyncPrinter;
+pub use classify::{ByteClassifier, DefaultClassifier, Style};
 pub use colors::ColorType;
+pub use export::OutputFormat;
+pub use header::DumpHeader;
+pub use hexdump::HexDump;
+#[cfg(feature = "std")]
 pub use input::Input;
-
-use std::io::{self, BufReader, Read, Write};
-
+pub use layout::Layout;
+#[cfg(feature = "mmap")]
+pub use mmap::MMAP_THRESHOLD;
+#[cfg(feature = "parallel")]
+use render::ForkRenderer;
+pub use render::{Dumper, Renderer};
+#[cfg(feature = "std")]
+pub use reverse::Reverser;
+pub use rows::{Row, Rows, SqueezeInfo};
+pub use summary::DumpSummary;
+
+use export::{ExportPrinter, StreamState};
+use json::JsonRenderer;
+use layout::CompatPrinter;
+use terminal::TerminalRenderer;
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use core::fmt;
+
+use io::{InputReader, Read, Write};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Base {
     Binary,
     Octal,
@@ -13,7 +71,28 @@ pub enum Base {
     Hexadecimal,
 }
 
-#[derive(Copy, Clone)]
+impl Base {
+    /// The number of digits of a byte.
+    fn digits(self) -> u8 {
+        match self {
+            Base::Binary => 8,
+            Base::Octal => 3,
+            Base::Decimal => 3,
+            Base::Hexadecimal => 2,
+        }
+    }
+
+    fn format_byte(self, byte: u8) -> String {
+        match self {
+            Base::Binary => format!("{byte:08b}"),
+            Base::Octal => format!("{byte:03o}"),
+            Base::Decimal => format!("{byte:03}"),
+            Base::Hexadecimal => format!("{byte:02x}"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ByteCategory {
     Null,
     AsciiPrintable,
@@ -22,8 +101,31 @@ pub enum ByteCategory {
     NonAscii,
 }
 
-#[derive(Copy, Clone, Debug, Default)]
+impl ByteCategory {
+    pub const ALL: [ByteCategory; 5] = [
+        ByteCategory::Null,
+        ByteCategory::AsciiPrintable,
+        ByteCategory::AsciiWhitespace,
+        ByteCategory::AsciiOther,
+        ByteCategory::NonAscii,
+    ];
+
+    /// The name of the category in structured output.
+    pub fn name(self) -> &'static str {
+        match self {
+            ByteCategory::Null => "null",
+            ByteCategory::AsciiPrintable => "ascii_printable",
+            ByteCategory::AsciiWhitespace => "ascii_whitespace",
+            ByteCategory::AsciiOther => "ascii_other",
+            ByteCategory::NonAscii => "non_ascii",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 #[non_exhaustive]
 pub enum CharacterTable {
     /// Show printable ASCII characters as-is, '⋄' for NULL bytes, ' ' for
@@ -37,15 +139,19 @@ pub enum CharacterTable {
 
     /// Show printable EBCDIC as-is, ' ' for space, '.' for everything else.
     #[cfg_attr(feature = "cli", value(name = "codepage-1047"))]
+    #[cfg_attr(feature = "serde", serde(rename = "codepage-1047"))]
     CP1047,
 
     /// Uses code page 437 (for non-ASCII bytes).
     #[cfg_attr(feature = "cli", value(name = "codepage-437"))]
+    #[cfg_attr(feature = "serde", serde(rename = "codepage-437"))]
     CP437,
 }
 
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Endianness {
     /// Print out groups in little-endian format.
     Little,
@@ -55,14 +161,6 @@ pub enum Endianness {
     Big,
 }
 
-#[derive(PartialEq)]
-enum Squeezer {
-    Print,
-    Delete,
-    Ignore,
-    Disabled,
-}
-
 #[derive(Copy, Clone)]
 struct Byte(u8);
 
@@ -122,8 +220,10 @@ struct BorderElements {
     right_corner: char,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum BorderStyle {
     /// Draw a border with Unicode characters.
     #[default]
@@ -173,6 +273,44 @@ impl BorderStyle {
         }
     }
 
+    /// The border above the row of a [`DumpHeader`].
+    fn title_elems(&self) -> Option<BorderElements> {
+        match self {
+            BorderStyle::Unicode => Some(BorderElements {
+                left_corner: '┌',
+                horizontal_line: '─',
+                column_separator: '─',
+                right_corner: '┐',
+            }),
+            BorderStyle::Ascii => Some(BorderElements {
+                left_corner: '+',
+                horizontal_line: '-',
+                column_separator: '-',
+                right_corner: '+',
+            }),
+            BorderStyle::None => None,
+        }
+    }
+
+    /// The border between the row of a [`DumpHeader`] and the rows of the dump.
+    fn title_separator_elems(&self) -> Option<BorderElements> {
+        match self {
+            BorderStyle::Unicode => Some(BorderElements {
+                left_corner: '├',
+                horizontal_line: '─',
+                column_separator: '┬',
+                right_corner: '┤',
+            }),
+            BorderStyle::Ascii => Some(BorderElements {
+                left_corner: '+',
+                horizontal_line: '-',
+                column_separator: '+',
+                right_corner: '+',
+            }),
+            BorderStyle::None => None,
+        }
+    }
+
     fn outer_sep(&self) -> char {
         match self {
             BorderStyle::Unicode => '│',
@@ -190,7 +328,43 @@ impl BorderStyle {
     }
 }
 
-pub struct PrinterBuilder<Writer: Write> {
+/// The view settings of a [`PrinterBuilder`], e.g. to persist them with the `serde` feature.
+/// Missing fields are deserialized as their defaults.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[non_exhaustive]
+pub struct PrinterConfig {
+    pub panels: u64,
+    pub group_size: u8,
+    pub base: Base,
+    pub endianness: Endianness,
+    pub character_table: CharacterTable,
+    pub border_style: BorderStyle,
+    pub show_color: bool,
+    pub show_char_panel: bool,
+    pub show_position_panel: bool,
+    pub squeeze: bool,
+}
+
+impl Default for PrinterConfig {
+    fn default() -> Self {
+        PrinterConfig {
+            panels: 2,
+            group_size: 1,
+            base: Base::Hexadecimal,
+            endianness: Endianness::Big,
+            character_table: CharacterTable::Default,
+            border_style: BorderStyle::Unicode,
+            show_color: true,
+            show_char_panel: true,
+            show_position_panel: true,
+            squeeze: true,
+        }
+    }
+}
+
+pub struct PrinterBuilder<Writer> {
     writer: Writer,
     show_color: bool,
     show_char_panel: bool,
@@ -202,22 +376,57 @@ pub struct PrinterBuilder<Writer: Write> {
     base: Base,
     endianness: Endianness,
     character_table: CharacterTable,
+    layout: Layout,
+    output_format: OutputFormat,
+    bytes_per_line: Option<usize>,
+    variable_name: Option<String>,
+    byte_classifier: Option<Box<dyn ByteClassifier + Send>>,
+    header: Option<DumpHeader>,
+    threads: usize,
 }
 
-impl<Writer: Write> PrinterBuilder<Writer> {
+impl<Writer> PrinterBuilder<Writer> {
     pub fn new(writer: Writer) -> Self {
+        Self::from_config(writer, PrinterConfig::default())
+    }
+
+    /// A builder with the settings of `config`.
+    pub fn from_config(writer: Writer, config: PrinterConfig) -> Self {
         PrinterBuilder {
             writer,
-            show_color: true,
-            show_char_panel: true,
-            show_position_panel: true,
-            border_style: BorderStyle::Unicode,
-            use_squeeze: true,
-            panels: 2,
-            group_size: 1,
-            base: Base::Hexadecimal,
-            endianness: Endianness::Big,
-            character_table: CharacterTable::Default,
+            show_color: config.show_color,
+            show_char_panel: config.show_char_panel,
+            show_position_panel: config.show_position_panel,
+            border_style: config.border_style,
+            use_squeeze: config.squeeze,
+            panels: config.panels,
+            group_size: config.group_size,
+            base: config.base,
+            endianness: config.endianness,
+            character_table: config.character_table,
+            layout: Layout::Hexyl,
+            output_format: OutputFormat::Dump,
+            bytes_per_line: None,
+            variable_name: None,
+            byte_classifier: None,
+            header: None,
+            threads: 1,
+        }
+    }
+
+    /// The settings of the builder, see [`PrinterBuilder::from_config`].
+    pub fn to_config(&self) -> PrinterConfig {
+        PrinterConfig {
+            panels: self.panels,
+            group_size: self.group_size,
+            base: self.base,
+            endianness: self.endianness,
+            character_table: self.character_table,
+            border_style: self.border_style,
+            show_color: self.show_color,
+            show_char_panel: self.show_char_panel,
+            show_position_panel: self.show_position_panel,
+            squeeze: self.use_squeeze,
         }
     }
 
@@ -271,8 +480,128 @@ impl<Writer: Write> PrinterBuilder<Writer> {
         self
     }
 
+    /// Emulate the output of another hex dump tool instead of drawing hexyl's panels.
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Produce something other than a hex dump, e.g. a string literal of the input.
+    pub fn output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    /// The number of bytes per line for output formats that are not hex dumps. `0` disables
+    /// line wrapping.
+    pub fn bytes_per_line(mut self, bytes_per_line: usize) -> Self {
+        self.bytes_per_line = Some(bytes_per_line);
+        self
+    }
+
+    /// The name of the variable for the array output formats. Characters that are not valid in
+    /// an identifier are replaced by underscores.
+    pub fn variable_name(mut self, variable_name: impl Into<String>) -> Self {
+        self.variable_name = Some(variable_name.into());
+        self
+    }
+
+    /// Decides the color and the character panel glyph of every byte, instead of the
+    /// [`DefaultClassifier`]. Only hexyl's own layout of a dump and HTML show the styles, and the
+    /// colors only in HTML or if [`PrinterBuilder::show_color`] is enabled. Other layouts and
+    /// output formats fail to build.
+    pub fn byte_classifier(mut self, classifier: impl ByteClassifier + Send + 'static) -> Self {
+        self.byte_classifier = Some(Box::new(classifier));
+        self
+    }
+
+    /// Shows `header` in a row inside the top border, e.g. the name of the input. Only hexyl's
+    /// own layout of a dump and HTML show it, other layouts and output formats fail to build.
+    pub fn header(mut self, header: DumpHeader) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Renders the rows of [`Printer::print_all`] on `threads` threads, or on one thread per CPU
+    /// if it is 0. The input is read in batches of a few hundred kilobytes per thread, and the
+    /// output is the same as with a single thread. Dumps with a
+    /// [`byte_classifier`](PrinterBuilder::byte_classifier), other layouts and formats that are
+    /// not printed row by row are always rendered on the calling thread.
+    #[cfg(feature = "parallel")]
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Replaces the writer of the builder, and returns the old one.
+    #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+    fn replace_writer<W>(self, writer: W) -> (PrinterBuilder<W>, Writer) {
+        let builder = PrinterBuilder {
+            writer,
+            show_color: self.show_color,
+            show_char_panel: self.show_char_panel,
+            show_position_panel: self.show_position_panel,
+            border_style: self.border_style,
+            use_squeeze: self.use_squeeze,
+            panels: self.panels,
+            group_size: self.group_size,
+            base: self.base,
+            endianness: self.endianness,
+            character_table: self.character_table,
+            layout: self.layout,
+            output_format: self.output_format,
+            bytes_per_line: self.bytes_per_line,
+            variable_name: self.variable_name,
+            byte_classifier: self.byte_classifier,
+            header: self.header,
+            threads: self.threads,
+        };
+        (builder, self.writer)
+    }
+}
+
+impl<Writer: Write> PrinterBuilder<Writer> {
+    /// Builds the printer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is invalid, see [`PrinterBuilder::try_build`].
     pub fn build(self) -> Printer<Writer> {
-        Printer::new(
+        match self.try_build() {
+            Ok(printer) => printer,
+            Err(e) => panic!("invalid printer configuration: {e}"),
+        }
+    }
+
+    /// Builds the printer, or returns why the configuration is invalid.
+    pub fn try_build(self) -> Result<Printer<Writer>, ConfigError> {
+        if self.panels == 0 {
+            return Err(ConfigError::NoPanels);
+        }
+        if self.panels > MAX_PANELS {
+            return Err(ConfigError::TooManyPanels(self.panels));
+        }
+        if !matches!(self.group_size, 1 | 2 | 4 | 8) {
+            return Err(ConfigError::InvalidGroupSize(self.group_size));
+        }
+        if self.layout != Layout::Hexyl && self.output_format != OutputFormat::Dump {
+            return Err(ConfigError::LayoutWithoutDump);
+        }
+        if self.bytes_per_line.is_some() && !self.output_format.has_lines() {
+            return Err(ConfigError::UnusedBytesPerLine);
+        }
+        if self.variable_name.is_some() && !self.output_format.is_array() {
+            return Err(ConfigError::UnusedVariableName);
+        }
+        let shows_styles = self.layout == Layout::Hexyl
+            && (self.output_format == OutputFormat::Dump || self.output_format.is_html());
+        if self.header.is_some() && !shows_styles {
+            return Err(ConfigError::UnusedHeader);
+        }
+        if self.byte_classifier.is_some() && !shows_styles {
+            return Err(ConfigError::UnusedByteClassifier);
+        }
+        Ok(Printer::new(
             self.writer,
             self.show_color,
             self.show_char_panel,
@@ -284,35 +613,240 @@ impl<Writer: Write> PrinterBuilder<Writer> {
             self.base,
             self.endianness,
             self.character_table,
-        )
+            self.layout,
+            self.output_format,
+            self.bytes_per_line,
+            self.variable_name
+                .unwrap_or_else(|| export::DEFAULT_VARIABLE_NAME.to_owned()),
+            self.byte_classifier,
+            self.header,
+            self.threads,
+        ))
+    }
+}
+
+/// The largest number of panels of a [`PrinterBuilder`], which makes rows of 8 KiB.
+pub const MAX_PANELS: u64 = 1024;
+
+/// An invalid configuration of a [`PrinterBuilder`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// The number of panels is 0.
+    NoPanels,
+    /// The number of panels is larger than [`MAX_PANELS`].
+    TooManyPanels(u64),
+    /// The group size is not 1, 2, 4 or 8.
+    InvalidGroupSize(u8),
+    /// A layout other than [`Layout::Hexyl`] is combined with an output format other than
+    /// [`OutputFormat::Dump`].
+    LayoutWithoutDump,
+    /// The number of bytes per line is set for an output format that isn't the plain hex or an
+    /// array format.
+    UnusedBytesPerLine,
+    /// The variable name is set for an output format that isn't an array format.
+    UnusedVariableName,
+    /// The header is set for a layout or an output format that isn't hexyl's own dump or HTML.
+    UnusedHeader,
+    /// The byte classifier is set for a layout or an output format that isn't hexyl's own dump or
+    /// HTML.
+    UnusedByteClassifier,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::NoPanels => write!(f, "the number of panels must be at least 1"),
+            ConfigError::TooManyPanels(panels) => write!(
+                f,
+                "{panels} panels are too many, there can be at most {MAX_PANELS}"
+            ),
+            ConfigError::InvalidGroupSize(group_size) => write!(
+                f,
+                "invalid group size {group_size}, it must be 1, 2, 4 or 8"
+            ),
+            ConfigError::LayoutWithoutDump => write!(
+                f,
+                "layouts other than hexyl only apply to the dump output format"
+            ),
+            ConfigError::UnusedBytesPerLine => write!(
+                f,
+                "the bytes per line only apply to the plain hex and array output formats"
+            ),
+            ConfigError::UnusedVariableName => write!(
+                f,
+                "the variable name only applies to the array output formats"
+            ),
+            ConfigError::UnusedHeader => write!(
+                f,
+                "the header only applies to the hexyl layout of a dump and to HTML"
+            ),
+            ConfigError::UnusedByteClassifier => write!(
+                f,
+                "the byte classifier only applies to the hexyl layout of a dump and to HTML"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConfigError {}
+
+/// The renderers of the output formats that [`Printer`] renders row by row.
+enum RowRenderer<Writer: Write> {
+    Terminal(Box<TerminalRenderer<Writer>>),
+    Json(JsonRenderer<Writer>),
+}
+
+impl<Writer: Write> RowRenderer<Writer> {
+    fn writer(&mut self) -> &mut Writer {
+        match self {
+            RowRenderer::Terminal(r) => &mut r.writer,
+            RowRenderer::Json(r) => &mut r.writer,
+        }
+    }
+}
+
+impl<Writer: Write> Renderer for RowRenderer<Writer> {
+    fn begin_dump(&mut self) -> io::Result<()> {
+        match self {
+            RowRenderer::Terminal(r) => r.begin_dump(),
+            RowRenderer::Json(r) => r.begin_dump(),
+        }
+    }
+
+    fn empty_dump(&mut self) -> io::Result<()> {
+        match self {
+            RowRenderer::Terminal(r) => r.empty_dump(),
+            RowRenderer::Json(r) => r.empty_dump(),
+        }
+    }
+
+    fn end_dump(&mut self) -> io::Result<()> {
+        match self {
+            RowRenderer::Terminal(r) => r.end_dump(),
+            RowRenderer::Json(r) => r.end_dump(),
+        }
+    }
+
+    fn begin_row(&mut self, offset: u64) -> io::Result<()> {
+        match self {
+            RowRenderer::Terminal(r) => r.begin_row(offset),
+            RowRenderer::Json(r) => r.begin_row(offset),
+        }
+    }
+
+    fn byte(&mut self, index: usize, byte: u8) -> io::Result<()> {
+        match self {
+            RowRenderer::Terminal(r) => r.byte(index, byte),
+            RowRenderer::Json(r) => r.byte(index, byte),
+        }
+    }
+
+    fn partial_row(&mut self, len: usize) -> io::Result<()> {
+        match self {
+            RowRenderer::Terminal(r) => r.partial_row(len),
+            RowRenderer::Json(r) => r.partial_row(len),
+        }
+    }
+
+    fn char_cell(&mut self, index: usize, byte: u8) -> io::Result<()> {
+        match self {
+            RowRenderer::Terminal(r) => r.char_cell(index, byte),
+            RowRenderer::Json(r) => r.char_cell(index, byte),
+        }
+    }
+
+    fn unreadable_byte(&mut self, index: usize) -> io::Result<()> {
+        match self {
+            RowRenderer::Terminal(r) => r.unreadable_byte(index),
+            RowRenderer::Json(r) => r.unreadable_byte(index),
+        }
+    }
+
+    fn unreadable_char(&mut self, index: usize) -> io::Result<()> {
+        match self {
+            RowRenderer::Terminal(r) => r.unreadable_char(index),
+            RowRenderer::Json(r) => r.unreadable_char(index),
+        }
+    }
+
+    fn end_row(&mut self) -> io::Result<()> {
+        match self {
+            RowRenderer::Terminal(r) => r.end_row(),
+            RowRenderer::Json(r) => r.end_row(),
+        }
+    }
+
+    fn row(&mut self, offset: u64, row: &[u8], row_len: usize) -> io::Result<()> {
+        match self {
+            RowRenderer::Terminal(r) => r.row(offset, row, row_len),
+            RowRenderer::Json(r) => r.row(offset, row, row_len),
+        }
+    }
+
+    fn squeeze_marker(&mut self, offset: u64) -> io::Result<()> {
+        match self {
+            RowRenderer::Terminal(r) => r.squeeze_marker(offset),
+            RowRenderer::Json(r) => r.squeeze_marker(offset),
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<Writer: Write> ForkRenderer for RowRenderer<Writer> {
+    type Fork = RowRenderer<Vec<u8>>;
+
+    fn can_fork(&self) -> bool {
+        match self {
+            RowRenderer::Terminal(r) => r.can_fork(),
+            RowRenderer::Json(_) => true,
+        }
+    }
+
+    fn fork(&self) -> Self::Fork {
+        match self {
+            RowRenderer::Terminal(r) => RowRenderer::Terminal(Box::new(r.fork())),
+            RowRenderer::Json(r) => RowRenderer::Json(r.fork()),
+        }
+    }
+
+    fn take_output(fork: &mut Self::Fork) -> Vec<u8> {
+        core::mem::take(fork.writer())
+    }
+
+    fn write_output(&mut self, output: &[u8]) -> io::Result<()> {
+        self.writer().write_all(output)
+    }
+
+    fn resume(&mut self, fork: &Self::Fork) {
+        match (self, fork) {
+            (RowRenderer::Terminal(r), RowRenderer::Terminal(fork)) => r.resume(fork),
+            (RowRenderer::Json(r), RowRenderer::Json(fork)) => r.resume(fork),
+            _ => unreachable!("a fork renders the same format"),
+        }
     }
 }
 
+/// Prints a dump of everything that is read with [`Printer::print_all`], or that is written to it
+/// as an [`io::Write`] followed by [`Printer::finish`].
 pub struct Printer<Writer: Write> {
-    idx: u64,
-    /// the buffer containing all the bytes in a line for character printing
-    line_buf: Vec<u8>,
-    writer: Writer,
-    show_char_panel: bool,
-    show_position_panel: bool,
-    show_color: bool,
-    curr_color: Option<ColorType>,
-    border_style: BorderStyle,
-    byte_hex_panel: Vec<String>,
-    byte_char_panel: Vec<String>,
-    // same as previous but in Fixed(242) gray color, for position panel
-    byte_hex_panel_g: Vec<String>,
-    squeezer: Squeezer,
+    dumper: Dumper<RowRenderer<Writer>>,
+    /// The written input of the formats that need the whole input, which are only printed by
+    /// `finish`.
+    pending: Vec<u8>,
+    /// The state of the written output of the formats that are printed as the input is written.
+    stream: StreamState,
+    use_squeeze: bool,
     display_offset: u64,
-    /// The number of panels to draw.
-    panels: u64,
-    squeeze_byte: usize,
-    /// The number of octets per group.
-    group_size: u8,
-    /// The number of digits used to write the base.
-    base_digits: u8,
-    /// Whether to show groups in little or big endian format.
-    endianness: Endianness,
+    layout: Layout,
+    output_format: OutputFormat,
+    bytes_per_line: Option<usize>,
+    variable_name: String,
+    /// The number of threads that render the rows of `print_all`, see
+    /// [`PrinterBuilder::threads`].
+    #[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+    threads: usize,
 }
 
 impl<Writer: Write> Printer<Writer> {
@@ -329,445 +863,273 @@ impl<Writer: Write> Printer<Writer> {
         base: Base,
         endianness: Endianness,
         character_table: CharacterTable,
+        layout: Layout,
+        output_format: OutputFormat,
+        bytes_per_line: Option<usize>,
+        variable_name: String,
+        byte_classifier: Option<Box<dyn ByteClassifier + Send>>,
+        header: Option<DumpHeader>,
+        threads: usize,
     ) -> Printer<Writer> {
+        let renderer = if output_format.is_json() {
+            RowRenderer::Json(JsonRenderer::new(
+                writer,
+                output_format == OutputFormat::Ndjson,
+                base,
+                group_size,
+                endianness,
+                character_table,
+            ))
+        } else {
+            RowRenderer::Terminal(Box::new(TerminalRenderer::new(
+                writer,
+                show_color,
+                show_char_panel,
+                show_position_panel,
+                border_style,
+                panels,
+                group_size,
+                base,
+                endianness,
+                character_table,
+                output_format,
+                byte_classifier,
+                header,
+            )))
+        };
         Printer {
-            idx: 0,
-            line_buf: vec![0x0; 8 * panels as usize],
-            writer,
-            show_char_panel,
-            show_position_panel,
-            show_color,
-            curr_color: None,
-            border_style,
-            byte_hex_panel: (0u8..=u8::MAX)
-                .map(|i| match base {
-                    Base::Binary => format!("{i:08b}"),
-                    Base::Octal => format!("{i:03o}"),
-                    Base::Decimal => format!("{i:03}"),
-                    Base::Hexadecimal => format!("{i:02x}"),
-                })
-                .collect(),
-            byte_char_panel: (0u8..=u8::MAX)
-                .map(|i| format!("{}", Byte(i).as_char(character_table)))
-                .collect(),
-            byte_hex_panel_g: (0u8..=u8::MAX).map(|i| format!("{i:02x}")).collect(),
-            squeezer: if use_squeeze {
-                Squeezer::Ignore
-            } else {
-                Squeezer::Disabled
-            },
+            dumper: Dumper::new(renderer, panels).enable_squeezing(use_squeeze),
+            pending: vec![],
+            stream: StreamState::default(),
+            use_squeeze,
             display_offset: 0,
-            panels,
-            squeeze_byte: 0x00,
-            group_size,
-            base_digits: match base {
-                Base::Binary => 8,
-                Base::Octal => 3,
-                Base::Decimal => 3,
-                Base::Hexadecimal => 2,
-            },
-            endianness,
+            layout,
+            output_format,
+            bytes_per_line,
+            variable_name,
+            threads,
         }
     }
 
     pub fn display_offset(&mut self, display_offset: u64) -> &mut Self {
+        self.dumper.display_offset(display_offset);
         self.display_offset = display_offset;
         self
     }
 
-    fn panel_sz(&self) -> usize {
-        // add one to include the trailing space of a group
-        let group_sz = self.base_digits as usize * self.group_size as usize + 1;
-        let group_per_panel = 8 / self.group_size as usize;
-        // add one to include the leading space
-        1 + group_sz * group_per_panel
+    pub fn print_header(&mut self) -> io::Result<()> {
+        match self.dumper.renderer_mut() {
+            RowRenderer::Terminal(r) => r.print_header(),
+            RowRenderer::Json(r) => r.begin_dump(),
+        }
     }
 
-    fn write_border(&mut self, border_elements: BorderElements) -> io::Result<()> {
-        let h = border_elements.horizontal_line;
-        let c = border_elements.column_separator;
-        let l = border_elements.left_corner;
-        let r = border_elements.right_corner;
-        let h8 = h.to_string().repeat(8);
-        let h_repeat = h.to_string().repeat(self.panel_sz());
+    pub fn print_footer(&mut self) -> io::Result<()> {
+        match self.dumper.renderer_mut() {
+            RowRenderer::Terminal(r) => r.print_footer(),
+            RowRenderer::Json(r) => r.end_dump(),
+        }
+    }
 
-        if self.show_position_panel {
-            write!(self.writer, "{l}{h8}{c}")?;
-        } else {
-            write!(self.writer, "{l}")?;
+    pub fn print_char_panel(&mut self) -> io::Result<()> {
+        let line = self.dumper.line().to_vec();
+        match self.dumper.renderer_mut() {
+            RowRenderer::Terminal(r) => r.print_char_panel(&line),
+            RowRenderer::Json(_) => Ok(()),
         }
+    }
 
-        for _ in 0..self.panels - 1 {
-            write!(self.writer, "{h_repeat}{c}")?;
+    pub fn print_bytes(&mut self) -> io::Result<()> {
+        let line = self.dumper.line().to_vec();
+        match self.dumper.renderer_mut() {
+            RowRenderer::Terminal(r) => r.print_bytes(&line),
+            RowRenderer::Json(_) => Ok(()),
         }
-        if self.show_char_panel {
-            write!(self.writer, "{h_repeat}{c}")?;
+    }
+
+    #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+    pub(crate) fn writer_mut(&mut self) -> &mut Writer {
+        self.dumper.renderer_mut().writer()
+    }
+
+    /// Whether the dump has begun and is not finished yet.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn is_started(&self) -> bool {
+        if self.has_rows() {
+            self.dumper.is_started()
+        } else if self.output_format.is_streamed() {
+            self.stream.is_started()
         } else {
-            write!(self.writer, "{h_repeat}")?;
+            !self.pending.is_empty()
         }
+    }
 
-        if self.show_char_panel {
-            for _ in 0..self.panels - 1 {
-                write!(self.writer, "{h8}{c}")?;
-            }
-            writeln!(self.writer, "{h8}{r}")?;
+    /// The offset of the next written byte, including the display offset.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn position(&self) -> u64 {
+        if self.has_rows() {
+            self.dumper.position()
+        } else if self.output_format.is_streamed() {
+            self.summary().end_offset()
         } else {
-            writeln!(self.writer, "{r}")?;
+            self.display_offset + self.pending.len() as u64
         }
+    }
 
-        Ok(())
+    /// Whether the output is rendered row by row by the dumper, everything else reads the whole
+    /// input at once.
+    fn has_rows(&self) -> bool {
+        self.output_format.has_rows()
+            && (self.output_format != OutputFormat::Dump || self.layout == Layout::Hexyl)
     }
 
-    pub fn print_header(&mut self) -> io::Result<()> {
-        if let Some(e) = self.border_style.header_elems() {
-            self.write_border(e)?
-        }
-        Ok(())
+    /// Whether `print_all` renders the rows on several threads.
+    #[cfg(feature = "parallel")]
+    fn is_parallel(&self) -> bool {
+        self.threads != 1 && self.has_rows() && self.dumper.renderer().can_fork()
     }
 
-    pub fn print_footer(&mut self) -> io::Result<()> {
-        if let Some(e) = self.border_style.footer_elems() {
-            self.write_border(e)?
+    fn print_all_at_once<Reader: Read>(&mut self, reader: Reader) -> io::Result<()> {
+        let mut summary = DumpSummary::new(self.display_offset);
+        let reader = CountingReader {
+            inner: reader,
+            summary: &mut summary,
+        };
+        let writer = self.dumper.renderer_mut().writer();
+        if self.output_format.has_rows() {
+            CompatPrinter::new(writer, self.layout, self.use_squeeze, self.display_offset)
+                .print_all(reader)?;
+        } else {
+            ExportPrinter::new(
+                writer,
+                self.output_format,
+                self.bytes_per_line,
+                &self.variable_name,
+            )
+            .print_all(reader)?;
         }
+        self.dumper.set_summary(summary);
         Ok(())
     }
 
-    fn print_position_panel(&mut self) -> io::Result<()> {
-        self.writer.write_all(
-            self.border_style
-                .outer_sep()
-                .encode_utf8(&mut [0; 4])
-                .as_bytes(),
-        )?;
-        if self.show_color {
-            self.writer.write_all(ColorType::Offset.ansi_bytes())?;
-        }
-        if self.show_position_panel {
-            match self.squeezer {
-                Squeezer::Print => {
-                    self.writer.write_all(b"*")?;
-                    if self.show_color {
-                        self.writer.write_all(ColorType::Reset.ansi_bytes())?;
-                    }
-                    self.writer.write_all(b"       ")?;
-                }
-                Squeezer::Ignore | Squeezer::Disabled | Squeezer::Delete => {
-                    let byte_index: [u8; 8] = (self.idx + self.display_offset).to_be_bytes();
-                    let mut i = 0;
-                    while byte_index[i] == 0x0 && i < 4 {
-                        i += 1;
-                    }
-                    for &byte in byte_index.iter().skip(i) {
-                        self.writer
-                            .write_all(self.byte_hex_panel_g[byte as usize].as_bytes())?;
-                    }
-                    if self.show_color {
-                        self.writer.write_all(ColorType::Reset.ansi_bytes())?;
-                    }
-                }
-            }
-            self.writer.write_all(
-                self.border_style
-                    .outer_sep()
-                    .encode_utf8(&mut [0; 4])
-                    .as_bytes(),
-            )?;
+    /// Loop through the given `Reader`, printing until the `Reader` buffer
+    /// is exhausted. Returns the summary of the dump.
+    ///
+    /// Interrupted reads are retried. Other read errors are returned with the offset at which
+    /// they occurred, see [`io::ReadError`].
+    pub fn print_all<Reader: Read>(&mut self, reader: Reader) -> io::Result<DumpSummary> {
+        #[cfg(feature = "parallel")]
+        if self.is_parallel() {
+            return self.dumper.print_all_parallel(reader, self.threads);
+        }
+        if self.has_rows() {
+            self.dumper.print_all(reader)
+        } else {
+            self.print_all_at_once(InputReader::new(reader, self.display_offset))?;
+            Ok(self.summary().clone())
         }
-        Ok(())
     }
 
-    fn print_char(&mut self, i: u64) -> io::Result<()> {
-        match self.squeezer {
-            Squeezer::Print | Squeezer::Delete => self.writer.write_all(b" ")?,
-            Squeezer::Ignore | Squeezer::Disabled => {
-                if let Some(&b) = self.line_buf.get(i as usize) {
-                    let byte_color = Byte(b).color();
-                    if self.show_color && self.curr_color != Some(byte_color) {
-                        self.writer.write_all(byte_color.ansi_bytes())?;
-                        self.curr_color = Some(byte_color);
-                    }
-                    self.writer
-                        .write_all(self.byte_char_panel[b as usize].as_bytes())?;
-                } else {
-                    self.squeezer = Squeezer::Print;
-                }
-            }
-        }
-        if i == 8 * self.panels - 1 {
-            if self.show_color {
-                self.writer.write_all(ColorType::Reset.ansi_bytes())?;
-                self.curr_color = None;
-            }
-            self.writer.write_all(
-                self.border_style
-                    .outer_sep()
-                    .encode_utf8(&mut [0; 4])
-                    .as_bytes(),
-            )?;
-        } else if i % 8 == 7 {
-            if self.show_color {
-                self.writer.write_all(ColorType::Reset.ansi_bytes())?;
-                self.curr_color = None;
-            }
-            self.writer.write_all(
-                self.border_style
-                    .inner_sep()
-                    .encode_utf8(&mut [0; 4])
-                    .as_bytes(),
-            )?;
-        }
-
-        Ok(())
+    /// The summary of the current dump, or of the last one if it has ended.
+    pub fn summary(&self) -> &DumpSummary {
+        self.dumper.summary()
     }
 
-    pub fn print_char_panel(&mut self) -> io::Result<()> {
-        for i in 0..self.line_buf.len() {
-            self.print_char(i as u64)?;
+    /// Continues the written input with `len` bytes that could not be read, which are shown as
+    /// unreadable cells. Only the formats that are printed row by row can show them.
+    pub fn write_unreadable(&mut self, len: u64) -> io::Result<()> {
+        if self.has_rows() {
+            self.dumper.push_unreadable(len)
+        } else {
+            Err(io::Error::other(
+                "unreadable bytes can only be shown in a hexyl dump, as JSON or as HTML",
+            ))
         }
-        Ok(())
     }
 
-    fn print_byte(&mut self, i: usize, b: u8) -> io::Result<()> {
-        match self.squeezer {
-            Squeezer::Print => {
-                if !self.show_position_panel && i == 0 {
-                    if self.show_color {
-                        self.writer.write_all(ColorType::Offset.ansi_bytes())?;
-                    }
-                    self.writer
-                        .write_all(self.byte_char_panel[b'*' as usize].as_bytes())?;
-                    if self.show_color {
-                        self.writer.write_all(ColorType::Reset.ansi_bytes())?;
-                    }
-                } else if i % (self.group_size as usize) == 0 {
-                    self.writer.write_all(b" ")?;
-                }
-                for _ in 0..self.base_digits {
-                    self.writer.write_all(b" ")?;
-                }
+    fn push(&mut self, data: &[u8]) -> io::Result<()> {
+        if self.has_rows() {
+            self.dumper.push(data)
+        } else if self.output_format.is_streamed() {
+            if !self.stream.is_started() {
+                self.dumper
+                    .set_summary(DumpSummary::new(self.display_offset));
             }
-            Squeezer::Delete => self.writer.write_all(b"   ")?,
-            Squeezer::Ignore | Squeezer::Disabled => {
-                if i % (self.group_size as usize) == 0 {
-                    self.writer.write_all(b" ")?;
-                }
-                let byte_color = Byte(b).color();
-                if self.show_color && self.curr_color != Some(byte_color) {
-                    self.writer.write_all(byte_color.ansi_bytes())?;
-                    self.curr_color = Some(byte_color);
-                }
-                self.writer
-                    .write_all(self.byte_hex_panel[b as usize].as_bytes())?;
-            }
-        }
-        // byte is last in panel
-        if i % 8 == 7 {
-            if self.show_color {
-                self.curr_color = None;
-                self.writer.write_all(ColorType::Reset.ansi_bytes())?;
-            }
-            self.writer.write_all(b" ")?;
-            // byte is last in last panel
-            if i as u64 % (8 * self.panels) == 8 * self.panels - 1 {
-                self.writer.write_all(
-                    self.border_style
-                        .outer_sep()
-                        .encode_utf8(&mut [0; 4])
-                        .as_bytes(),
-                )?;
-            } else {
-                self.writer.write_all(
-                    self.border_style
-                        .inner_sep()
-                        .encode_utf8(&mut [0; 4])
-                        .as_bytes(),
-                )?;
-            }
-        }
-        Ok(())
-    }
-
-    fn reorder_buffer_to_little_endian(&self, buf: &mut [u8]) {
-        let n = buf.len();
-        let group_sz = self.group_size as usize;
-
-        for idx in (0..n).step_by(group_sz) {
-            let remaining = n - idx;
-            let total = remaining.min(group_sz);
-
-            buf[idx..idx + total].reverse();
+            self.dumper.summary_mut().add_bytes(data);
+            ExportPrinter::new(
+                self.dumper.renderer_mut().writer(),
+                self.output_format,
+                self.bytes_per_line,
+                &self.variable_name,
+            )
+            .push(&mut self.stream, data)
+        } else {
+            self.pending.extend_from_slice(data);
+            Ok(())
         }
     }
 
-    pub fn print_bytes(&mut self) -> io::Result<()> {
-        match self.endianness {
-            Endianness::Big => {
-                // we can't directly iterate over self.line_buf because that immutably borrows from
-                // self but print_byte needs &mut self. The bounds check while indexing can't be
-                // elided because technically self.line_buf could change length from the print_byte
-                // call, but that's still a win compared to cloning self.line_buf unconditionally.
-                for i in 0..self.line_buf.len() {
-                    self.print_byte(i, self.line_buf[i])?;
-                }
-            }
-            Endianness::Little => {
-                let mut buf = self.line_buf.clone();
-                self.reorder_buffer_to_little_endian(&mut buf);
-                for (i, b) in buf.into_iter().enumerate() {
-                    self.print_byte(i, b)?;
-                }
+    /// Ends the dump of the input that was written to the printer, like the end of the input in
+    /// [`Printer::print_all`]. Writing to the printer afterwards starts a new dump.
+    pub fn finish(&mut self) -> io::Result<()> {
+        if self.has_rows() {
+            self.dumper.finish()
+        } else if self.output_format.is_streamed() {
+            if !self.stream.is_started() {
+                self.dumper
+                    .set_summary(DumpSummary::new(self.display_offset));
             }
+            let mut printer = ExportPrinter::new(
+                self.dumper.renderer_mut().writer(),
+                self.output_format,
+                self.bytes_per_line,
+                &self.variable_name,
+            );
+            printer.end(&mut self.stream)?;
+            self.dumper.renderer_mut().writer().flush()
+        } else {
+            let pending = core::mem::take(&mut self.pending);
+            self.print_all_at_once(&pending[..])
         }
-
-        Ok(())
     }
+}
 
-    /// Loop through the given `Reader`, printing until the `Reader` buffer
-    /// is exhausted.
-    pub fn print_all<Reader: Read>(&mut self, reader: Reader) -> io::Result<()> {
-        let mut is_empty = true;
-
-        let mut buf = BufReader::new(reader);
-
-        let leftover = loop {
-            // read a maximum of 8 * self.panels bytes from the reader
-            if let Ok(n) = buf.read(&mut self.line_buf) {
-                if n > 0 && n < 8 * self.panels as usize {
-                    // if less are read, that indicates end of file after
-                    if is_empty {
-                        self.print_header()?;
-                        is_empty = false;
-                    }
-                    let mut leftover = n;
-                    // loop until input is ceased
-                    if let Some(s) = loop {
-                        if let Ok(n) = buf.read(&mut self.line_buf[leftover..]) {
-                            leftover += n;
-                            // there is no more input being read
-                            if n == 0 {
-                                self.line_buf.resize(leftover, 0);
-                                break Some(leftover);
-                            }
-                            // amount read has exceeded line buffer
-                            if leftover >= 8 * self.panels as usize {
-                                break None;
-                            }
-                        }
-                    } {
-                        break Some(s);
-                    };
-                } else if n == 0 {
-                    // if no bytes are read, that indicates end of file
-                    if self.squeezer == Squeezer::Delete {
-                        // empty the last line when ending is squeezed
-                        self.line_buf.clear();
-                        break Some(0);
-                    }
-                    break None;
-                }
-            }
-            if is_empty {
-                self.print_header()?;
-            }
-
-            // squeeze is active, check if the line is the same
-            // skip print if still squeezed, otherwise print and deactivate squeeze
-            if matches!(self.squeezer, Squeezer::Print | Squeezer::Delete) {
-                if self
-                    .line_buf
-                    .chunks_exact(std::mem::size_of::<usize>())
-                    .all(|w| usize::from_ne_bytes(w.try_into().unwrap()) == self.squeeze_byte)
-                {
-                    if self.squeezer == Squeezer::Delete {
-                        self.idx += 8 * self.panels;
-                        continue;
-                    }
-                } else {
-                    self.squeezer = Squeezer::Ignore;
-                }
-            }
-
-            // print the line
-            self.print_position_panel()?;
-            self.print_bytes()?;
-            if self.show_char_panel {
-                self.print_char_panel()?;
-            }
-            self.writer.write_all(b"\n")?;
-
-            if is_empty {
-                self.writer.flush()?;
-                is_empty = false;
-            }
-
-            // increment index to next line
-            self.idx += 8 * self.panels;
-
-            // change from print to delete if squeeze is still active
-            if self.squeezer == Squeezer::Print {
-                self.squeezer = Squeezer::Delete;
-            }
-
-            // repeat the first byte in the line until it's a usize
-            // compare that usize with each usize chunk in the line
-            // if they are all the same, change squeezer to print
-            let repeat_byte = (self.line_buf[0] as usize) * (usize::MAX / 255);
-            if !matches!(self.squeezer, Squeezer::Disabled | Squeezer::Delete)
-                && self
-                    .line_buf
-                    .chunks_exact(std::mem::size_of::<usize>())
-                    .all(|w| usize::from_ne_bytes(w.try_into().unwrap()) == repeat_byte)
-            {
-                self.squeezer = Squeezer::Print;
-                self.squeeze_byte = repeat_byte;
-            };
-        };
+/// Counts the bytes that are read from `inner`.
+struct CountingReader<'a, Reader: Read> {
+    inner: Reader,
+    summary: &'a mut DumpSummary,
+}
 
-        // special ending
+impl<Reader: Read> Read for CountingReader<'_, Reader> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.summary.add_bytes(&buf[..n]);
+        Ok(n)
+    }
+}
 
-        if is_empty {
-            self.base_digits = 2;
-            self.print_header()?;
-            if self.show_position_panel {
-                write!(self.writer, "{0:9}", "│")?;
-            }
-            write!(
-                self.writer,
-                "{0:2}{1:2$}{0}{0:>3$}",
-                "│",
-                "No content",
-                self.panel_sz() - 1,
-                self.panel_sz() + 1,
-            )?;
-            if self.show_char_panel {
-                write!(self.writer, "{0:>9}{0:>9}", "│")?;
-            }
-            writeln!(self.writer)?;
-        } else if let Some(n) = leftover {
-            // last line is incomplete
-            self.print_position_panel()?;
-            self.squeezer = Squeezer::Ignore;
-            self.print_bytes()?;
-            self.squeezer = Squeezer::Print;
-            for i in n..8 * self.panels as usize {
-                self.print_byte(i, 0)?;
-            }
-            if self.show_char_panel {
-                self.squeezer = Squeezer::Ignore;
-                self.print_char_panel()?;
-                self.squeezer = Squeezer::Print;
-                for i in n..8 * self.panels as usize {
-                    self.print_char(i as u64)?;
-                }
-            }
-            self.writer.write_all(b"\n")?;
-        }
+/// Feeds the input of the dump, which ends with [`Printer::finish`]. The rows are printed as soon
+/// as they are complete, unless the output format needs the whole input.
+#[cfg(feature = "std")]
+impl<Writer: Write> Write for Printer<Writer> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push(buf)?;
+        Ok(buf.len())
+    }
 
-        self.print_footer()?;
+    fn flush(&mut self) -> io::Result<()> {
+        self.dumper.renderer_mut().writer().flush()
+    }
+}
 
-        self.writer.flush()?;
+#[cfg(not(feature = "std"))]
+impl<Writer: Write> Write for Printer<Writer> {
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.push(buf)
+    }
 
-        Ok(())
+    fn flush(&mut self) -> io::Result<()> {
+        self.dumper.renderer_mut().writer().flush()
     }
 }
 
@@ -792,6 +1154,13 @@ mod tests {
             Base::Hexadecimal,
             Endianness::Big,
             CharacterTable::Default,
+            Layout::Hexyl,
+            OutputFormat::Dump,
+            None,
+            String::new(),
+            None,
+            None,
+            1,
         );
 
         printer.print_all(input).unwrap();
@@ -848,6 +1217,13 @@ mod tests {
             Base::Hexadecimal,
             Endianness::Big,
             CharacterTable::Default,
+            Layout::Hexyl,
+            OutputFormat::Dump,
+            None,
+            String::new(),
+            None,
+            None,
+            1,
         );
         printer.display_offset(0xdeadbeef);
 
@@ -883,6 +1259,13 @@ mod tests {
             Base::Hexadecimal,
             Endianness::Big,
             CharacterTable::Default,
+            Layout::Hexyl,
+            OutputFormat::Dump,
+            None,
+            String::new(),
+            None,
+            None,
+            1,
         );
 
         printer.print_all(input).unwrap();
@@ -944,6 +1327,13 @@ mod tests {
             Base::Hexadecimal,
             Endianness::Big,
             CharacterTable::Default,
+            Layout::Hexyl,
+            OutputFormat::Dump,
+            None,
+            String::new(),
+            None,
+            None,
+            1,
         );
 
         printer.print_all(input).unwrap();
@@ -951,4 +1341,690 @@ mod tests {
         let actual_string: &str = str::from_utf8(&output).unwrap();
         assert_eq!(actual_string, expected_string)
     }
+
+    #[test]
+    fn squeeze_partial_last_line() {
+        let mut input = vec![0; 16];
+        input.extend_from_slice(b"spam");
+        let expected_string = "\
+┌────────┬─────────────────────────┬─────────────────────────┬────────┬────────┐
+│00000000│ 00 00 00 00 00 00 00 00 ┊ 00 00 00 00 00 00 00 00 │⋄⋄⋄⋄⋄⋄⋄⋄┊⋄⋄⋄⋄⋄⋄⋄⋄│
+│00000010│ 73 70 61 6d             ┊                         │spam    ┊        │
+└────────┴─────────────────────────┴─────────────────────────┴────────┴────────┘
+"
+        .to_owned();
+        assert_print_all_output(&input[..], expected_string);
+    }
+
+    fn printer(output: &mut Vec<u8>, output_format: OutputFormat) -> Printer<&mut Vec<u8>> {
+        Printer::new(
+            output,
+            true,
+            true,
+            true,
+            BorderStyle::Unicode,
+            true,
+            2,
+            2,
+            Base::Hexadecimal,
+            Endianness::Little,
+            CharacterTable::Default,
+            Layout::Hexyl,
+            output_format,
+            None,
+            String::new(),
+            None,
+            None,
+            1,
+        )
+    }
+
+    #[test]
+    fn write_in_chunks() {
+        let mut inputs = vec![vec![], b"spam".to_vec(), vec![0; 16], vec![0; 64]];
+        let mut input = vec![0; 40];
+        input.extend_from_slice(b"spamspamspamspamspam");
+        input.extend_from_slice(&[0xff; 70]);
+        inputs.push(input);
+        inputs.push(b"\x01abc\x02\x03fed".to_vec());
+
+        for output_format in [
+            OutputFormat::Dump,
+            OutputFormat::Ndjson,
+            OutputFormat::CArray,
+            OutputFormat::PlainHex,
+            OutputFormat::CString,
+        ] {
+            for input in &inputs {
+                let mut expected = vec![];
+                printer(&mut expected, output_format)
+                    .print_all(&input[..])
+                    .unwrap();
+
+                for chunk_size in [1, 3, 16, 100] {
+                    let mut output = vec![];
+                    let mut printer = printer(&mut output, output_format);
+                    for chunk in input.chunks(chunk_size) {
+                        printer.write_all(chunk).unwrap();
+                    }
+                    printer.finish().unwrap();
+                    assert_eq!(printer.summary().bytes(), input.len() as u64);
+                    drop(printer);
+                    assert_eq!(
+                        str::from_utf8(&output).unwrap(),
+                        str::from_utf8(&expected).unwrap()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn write_prints_complete_rows() {
+        let mut output = vec![];
+        let mut printer = printer(&mut output, OutputFormat::Ndjson);
+        io::copy(&mut &b"0123456789abcdefghij"[..], &mut printer).unwrap();
+        drop(printer);
+        assert_eq!(str::from_utf8(&output).unwrap().lines().count(), 1);
+    }
+
+    #[test]
+    fn write_streams_plain_hex_and_strings() {
+        let mut output = vec![];
+        let mut plain_hex = printer(&mut output, OutputFormat::PlainHex);
+        plain_hex.write_all(&[0xab; 31]).unwrap();
+        assert_eq!(plain_hex.writer_mut().len(), 60 + 1 + 2);
+        assert!(plain_hex.pending.is_empty());
+        plain_hex.finish().unwrap();
+        drop(plain_hex);
+        assert_eq!(output, format!("{}\nab\n", "ab".repeat(30)).as_bytes());
+
+        let mut output = vec![];
+        let mut string = printer(&mut output, OutputFormat::RustString);
+        string.write_all(b"abc").unwrap();
+        assert_eq!(string.writer_mut().as_slice(), b"b\"abc");
+        string.finish().unwrap();
+        string.finish().unwrap();
+        drop(string);
+        assert_eq!(output, b"b\"abc\"\nb\"\"\n");
+    }
+
+    #[test]
+    fn json_rows() {
+        let mut input = b"spam".repeat(4);
+        input.extend_from_slice(&[0; 48]);
+        input.push(b'\n');
+
+        let mut output = vec![];
+        let mut printer = Printer::new(
+            &mut output,
+            true,
+            true,
+            true,
+            BorderStyle::Unicode,
+            true,
+            1,
+            2,
+            Base::Hexadecimal,
+            Endianness::Little,
+            CharacterTable::Default,
+            Layout::Hexyl,
+            OutputFormat::Ndjson,
+            None,
+            String::new(),
+            None,
+            None,
+            1,
+        );
+        printer.display_offset(0x100);
+        printer.print_all(&input[..]).unwrap();
+
+        let expected_string = r#"{"offset":256,"bytes":[115,112,97,109,115,112,97,109],"hex":["7073","6d61","7073","6d61"],"chars":"spamspam","categories":["ascii_printable","ascii_printable","ascii_printable","ascii_printable","ascii_printable","ascii_printable","ascii_printable","ascii_printable"],"squeezed":false}
+{"offset":264,"bytes":[115,112,97,109,115,112,97,109],"hex":["7073","6d61","7073","6d61"],"chars":"spamspam","categories":["ascii_printable","ascii_printable","ascii_printable","ascii_printable","ascii_printable","ascii_printable","ascii_printable","ascii_printable"],"squeezed":false}
+{"offset":272,"bytes":[0,0,0,0,0,0,0,0],"hex":["0000","0000","0000","0000"],"chars":"⋄⋄⋄⋄⋄⋄⋄⋄","categories":["null","null","null","null","null","null","null","null"],"squeezed":false}
+{"offset":280,"bytes":[0,0,0,0,0,0,0,0],"hex":["0000","0000","0000","0000"],"chars":"⋄⋄⋄⋄⋄⋄⋄⋄","categories":["null","null","null","null","null","null","null","null"],"squeezed":true}
+{"offset":320,"bytes":[10],"hex":["0a"],"chars":"_","categories":["ascii_whitespace"],"squeezed":false}
+"#;
+        assert_eq!(str::from_utf8(&output).unwrap(), expected_string);
+    }
+
+    #[test]
+    fn try_build_rejects_invalid_configuration() {
+        let build = |panels, group_size| {
+            PrinterBuilder::new(vec![])
+                .num_panels(panels)
+                .group_size(group_size)
+                .try_build()
+                .err()
+        };
+        assert_eq!(build(0, 1), Some(ConfigError::NoPanels));
+        assert_eq!(
+            build(u64::MAX, 1),
+            Some(ConfigError::TooManyPanels(u64::MAX))
+        );
+        assert_eq!(build(2, 3), Some(ConfigError::InvalidGroupSize(3)));
+        assert_eq!(build(2, 16), Some(ConfigError::InvalidGroupSize(16)));
+        for group_size in [1, 2, 4, 8] {
+            assert_eq!(build(3, group_size), None);
+        }
+        assert_eq!(
+            build(100_000_000_000, 1),
+            Some(ConfigError::TooManyPanels(100_000_000_000))
+        );
+        assert_eq!(
+            build(MAX_PANELS + 1, 1),
+            Some(ConfigError::TooManyPanels(MAX_PANELS + 1))
+        );
+        assert_eq!(build(MAX_PANELS, 1), None);
+    }
+
+    #[test]
+    fn try_build_rejects_unused_options() {
+        let build = |builder: PrinterBuilder<Vec<u8>>| builder.try_build().err();
+        let new = || PrinterBuilder::new(vec![]);
+
+        assert_eq!(
+            build(new().layout(Layout::Xxd).output_format(OutputFormat::Json)),
+            Some(ConfigError::LayoutWithoutDump)
+        );
+        assert_eq!(
+            build(new().layout(Layout::Od).output_format(OutputFormat::CArray)),
+            Some(ConfigError::LayoutWithoutDump)
+        );
+        assert_eq!(
+            build(new().layout(Layout::Xxd).output_format(OutputFormat::Dump)),
+            None
+        );
+
+        assert_eq!(
+            build(new().bytes_per_line(16)),
+            Some(ConfigError::UnusedBytesPerLine)
+        );
+        assert_eq!(
+            build(new().bytes_per_line(16).output_format(OutputFormat::Json)),
+            Some(ConfigError::UnusedBytesPerLine)
+        );
+        for format in [OutputFormat::PlainHex, OutputFormat::RustArray] {
+            assert_eq!(build(new().bytes_per_line(16).output_format(format)), None);
+        }
+
+        assert_eq!(
+            build(new().variable_name("buf")),
+            Some(ConfigError::UnusedVariableName)
+        );
+        assert_eq!(
+            build(
+                new()
+                    .variable_name("buf")
+                    .output_format(OutputFormat::PlainHex)
+            ),
+            Some(ConfigError::UnusedVariableName)
+        );
+        assert_eq!(
+            build(
+                new()
+                    .variable_name("buf")
+                    .output_format(OutputFormat::GoArray)
+            ),
+            None
+        );
+
+        let header = || DumpHeader {
+            name: Some("input".into()),
+            size: None,
+            modified: None,
+            range: None,
+        };
+        assert_eq!(
+            build(new().header(header()).layout(Layout::Xxd)),
+            Some(ConfigError::UnusedHeader)
+        );
+        assert_eq!(
+            build(new().header(header()).output_format(OutputFormat::Json)),
+            Some(ConfigError::UnusedHeader)
+        );
+        assert_eq!(
+            build(new().byte_classifier(DefaultClassifier).layout(Layout::Od)),
+            Some(ConfigError::UnusedByteClassifier)
+        );
+        assert_eq!(
+            build(
+                new()
+                    .byte_classifier(DefaultClassifier)
+                    .output_format(OutputFormat::CString)
+            ),
+            Some(ConfigError::UnusedByteClassifier)
+        );
+        for format in [OutputFormat::Dump, OutputFormat::Html] {
+            assert_eq!(
+                build(
+                    new()
+                        .header(header())
+                        .byte_classifier(DefaultClassifier)
+                        .output_format(format)
+                ),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn unreadable_bytes() {
+        let mut output = vec![];
+        let mut printer = PrinterBuilder::new(&mut output)
+            .show_color(false)
+            .num_panels(1)
+            .group_size(2)
+            .endianness(Endianness::Little)
+            .build();
+        printer.write_all(b"ab").unwrap();
+        printer.write_unreadable(3).unwrap();
+        printer.write_all(b"cd").unwrap();
+        printer.finish().unwrap();
+
+        let expected_string = "\
+┌────────┬─────────────────────┬────────┐
+│00000000│ 6261 ???? 63?? 64   │ab???cd │
+└────────┴─────────────────────┴────────┘
+"
+        .to_owned();
+        assert_eq!(str::from_utf8(&output).unwrap(), expected_string);
+    }
+
+    #[test]
+    fn summary_of_other_formats() {
+        let mut printer = PrinterBuilder::new(vec![])
+            .output_format(OutputFormat::PlainHex)
+            .build();
+        printer.display_offset(0x10);
+        let summary = printer.print_all(&b"\x00spam"[..]).unwrap();
+        assert_eq!(summary.bytes(), 5);
+        assert_eq!(summary.rows_printed(), 0);
+        assert_eq!(summary.end_offset(), 0x15);
+        assert_eq!(summary.category_count(ByteCategory::Null), 1);
+
+        printer.write_all(b"eggs").unwrap();
+        printer.finish().unwrap();
+        assert_eq!(printer.summary().bytes(), 4);
+    }
+
+    #[test]
+    fn byte_classifier() {
+        let input = b"\xff\xffhi\x00";
+        let classifier = |offset: u64, byte: u8| match byte {
+            0xff => Style::new(ColorType::Rgb(1, 2, 3)).with_glyph(if offset == 0x11 {
+                '▒'
+            } else {
+                '░'
+            }),
+            _ => DefaultClassifier.style(offset, byte),
+        };
+
+        let mut output = vec![];
+        let mut printer = PrinterBuilder::new(&mut output)
+            .show_color(false)
+            .num_panels(1)
+            .byte_classifier(classifier)
+            .build();
+        printer.display_offset(0x10);
+        printer.print_all(&input[..]).unwrap();
+        drop(printer);
+        assert!(str::from_utf8(&output)
+            .unwrap()
+            .contains("│00000010│ ff ff 68 69 00          │░▒hi⋄   │"));
+
+        let mut output = vec![];
+        PrinterBuilder::new(&mut output)
+            .num_panels(1)
+            .group_size(2)
+            .endianness(Endianness::Little)
+            .byte_classifier(|offset, byte| match offset {
+                1 => Style::new(ColorType::Rgb(1, 2, 3)),
+                _ => DefaultClassifier.style(offset, byte),
+            })
+            .build()
+            .print_all(&b"ab"[..])
+            .unwrap();
+        let output = str::from_utf8(&output).unwrap();
+        // the second byte is shown first in little endian
+        assert!(
+            output.contains(" \x1b[38;2;1;2;3m62\x1b[36m61"),
+            "{output:?}"
+        );
+
+        let mut output = vec![];
+        PrinterBuilder::new(&mut output)
+            .output_format(OutputFormat::HtmlFragment)
+            .byte_classifier(classifier)
+            .build()
+            .print_all(&input[..])
+            .unwrap();
+        let output = str::from_utf8(&output).unwrap();
+        assert!(output.contains("<span style=\"color: #010203\">ff ff"));
+        assert!(output.contains("<span style=\"color: #010203\">░░"));
+    }
+
+    #[test]
+    fn dump_after_empty_dump() {
+        for base in [Base::Binary, Base::Octal, Base::Decimal, Base::Hexadecimal] {
+            let builder = || {
+                PrinterBuilder::new(vec![])
+                    .show_color(false)
+                    .with_base(base)
+            };
+            let mut expected = builder().build();
+            expected.print_all(&b"abc"[..]).unwrap();
+
+            let mut printer = builder().build();
+            printer.print_all(io::empty()).unwrap();
+            printer.writer_mut().clear();
+            printer.print_all(&b"abc"[..]).unwrap();
+            assert_eq!(
+                str::from_utf8(printer.writer_mut()).unwrap(),
+                str::from_utf8(expected.writer_mut()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn html_classes_without_color() {
+        let print = |show_color| {
+            let mut output = vec![];
+            PrinterBuilder::new(&mut output)
+                .show_color(show_color)
+                .output_format(OutputFormat::HtmlFragment)
+                .build()
+                .print_all(&b"a\x00 \xff"[..])
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let output = print(false);
+        for class in [
+            "offset",
+            "ascii-printable",
+            "null",
+            "ascii-whitespace",
+            "non-ascii",
+        ] {
+            assert!(
+                output.contains(&format!("<span class=\"{class}\">")),
+                "{output}"
+            );
+        }
+        assert_eq!(output, print(true));
+    }
+
+    /// Forwards everything but whole rows, which are rendered with the calls of their cells.
+    struct Cells<R: Renderer>(R);
+
+    impl<R: Renderer> Renderer for Cells<R> {
+        fn begin_dump(&mut self) -> io::Result<()> {
+            self.0.begin_dump()
+        }
+
+        fn empty_dump(&mut self) -> io::Result<()> {
+            self.0.empty_dump()
+        }
+
+        fn end_dump(&mut self) -> io::Result<()> {
+            self.0.end_dump()
+        }
+
+        fn begin_row(&mut self, offset: u64) -> io::Result<()> {
+            self.0.begin_row(offset)
+        }
+
+        fn byte(&mut self, index: usize, byte: u8) -> io::Result<()> {
+            self.0.byte(index, byte)
+        }
+
+        fn partial_row(&mut self, len: usize) -> io::Result<()> {
+            self.0.partial_row(len)
+        }
+
+        fn char_cell(&mut self, index: usize, byte: u8) -> io::Result<()> {
+            self.0.char_cell(index, byte)
+        }
+
+        fn unreadable_byte(&mut self, index: usize) -> io::Result<()> {
+            self.0.unreadable_byte(index)
+        }
+
+        fn unreadable_char(&mut self, index: usize) -> io::Result<()> {
+            self.0.unreadable_char(index)
+        }
+
+        fn end_row(&mut self) -> io::Result<()> {
+            self.0.end_row()
+        }
+
+        fn squeeze_marker(&mut self, offset: u64) -> io::Result<()> {
+            self.0.squeeze_marker(offset)
+        }
+    }
+
+    #[test]
+    fn rows_are_rendered_like_cells() {
+        let mut input: Vec<u8> = (0..=255).collect();
+        input.extend_from_slice(&[0; 64]);
+        input.extend_from_slice(b"spam\t\n\r\x7f");
+        let builders: [fn() -> PrinterBuilder<Vec<u8>>; 8] = [
+            || PrinterBuilder::new(vec![]),
+            || PrinterBuilder::new(vec![]).show_color(false),
+            || {
+                PrinterBuilder::new(vec![])
+                    .num_panels(3)
+                    .group_size(4)
+                    .endianness(Endianness::Little)
+            },
+            || {
+                PrinterBuilder::new(vec![])
+                    .num_panels(1)
+                    .with_base(Base::Binary)
+                    .with_border_style(BorderStyle::Ascii)
+                    .character_table(CharacterTable::CP437)
+            },
+            || {
+                PrinterBuilder::new(vec![])
+                    .show_position_panel(false)
+                    .show_char_panel(false)
+            },
+            || PrinterBuilder::new(vec![]).output_format(OutputFormat::HtmlFragment),
+            || {
+                PrinterBuilder::new(vec![])
+                    .group_size(2)
+                    .endianness(Endianness::Little)
+                    .byte_classifier(|offset: u64, byte| match offset % 3 {
+                        0 => Style::new(ColorType::Rgb(1, 2, 3)).with_glyph('░'),
+                        _ => DefaultClassifier.style(offset, byte),
+                    })
+            },
+            || {
+                PrinterBuilder::new(vec![])
+                    .output_format(OutputFormat::Html)
+                    .byte_classifier(|offset: u64, byte| match offset % 3 {
+                        0 => Style::new(ColorType::Rgb(1, 2, 3)).with_glyph('<'),
+                        _ => DefaultClassifier.style(offset, byte),
+                    })
+            },
+        ];
+        for builder in builders {
+            let panels = builder().to_config().panels;
+            for len in [0, 5, 16, 100, input.len()] {
+                let mut printer = builder().build();
+                printer.print_all(&input[..len]).unwrap();
+                let expected = printer.writer_mut().clone();
+
+                let renderer = Cells(builder().build().dumper.into_renderer());
+                let mut dumper = Dumper::new(renderer, panels);
+                dumper.print_all(&input[..len]).unwrap();
+                let output = dumper.into_renderer().0.writer().clone();
+                assert_eq!(
+                    str::from_utf8(&output).unwrap(),
+                    str::from_utf8(&expected).unwrap()
+                );
+            }
+        }
+    }
+
+    /// Random bytes with squeezable runs that start, end and cross the chunks of a parallel dump
+    /// with 16 and 24 bytes per row.
+    #[cfg(feature = "parallel")]
+    fn chunked_input() -> Vec<u8> {
+        let mut state = 0x2545_f491_u32;
+        let mut input: Vec<u8> = (0..640_000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        for (k, chunk) in [(1, 65536), (2, 65536), (3, 65520), (5, 65536), (6, 65520)] {
+            let boundary = k * chunk;
+            for (start, end, byte) in [
+                (boundary - 32, boundary, 0),
+                (boundary - 48, boundary + 17, 0xff),
+                (boundary + 100, boundary + 148, 0),
+                (boundary + 148, boundary + 500, 1),
+            ] {
+                input[start..end].fill(byte);
+            }
+        }
+        input[6 * 65536 - 100..9 * 65536 + 100].fill(0);
+        input
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn parallel_output_is_sequential() {
+        let input = chunked_input();
+        let builders: [fn() -> PrinterBuilder<Vec<u8>>; 6] = [
+            || PrinterBuilder::new(vec![]),
+            || PrinterBuilder::new(vec![]).show_color(false),
+            || PrinterBuilder::new(vec![]).enable_squeezing(false),
+            || {
+                PrinterBuilder::new(vec![])
+                    .num_panels(3)
+                    .group_size(4)
+                    .endianness(Endianness::Little)
+            },
+            || PrinterBuilder::new(vec![]).output_format(OutputFormat::Html),
+            || PrinterBuilder::new(vec![]).output_format(OutputFormat::Json),
+        ];
+        for builder in builders {
+            for (skip, len) in [(0, input.len()), (5, 300_001), (3 * 65536 - 7, 65536 * 2)] {
+                let input = &input[..skip + len];
+                let mut printer = builder().build();
+                printer.display_offset(0x100);
+                printer.write_all(&input[..skip]).unwrap();
+                let expected_summary = printer.print_all(&input[skip..]).unwrap();
+                let expected = printer.writer_mut().clone();
+
+                for threads in [2, 3] {
+                    let mut printer = builder().threads(threads).build();
+                    printer.display_offset(0x100);
+                    printer.write_all(&input[..skip]).unwrap();
+                    let summary = printer.print_all(&input[skip..]).unwrap();
+                    assert_eq!(summary, expected_summary);
+                    assert!(printer.writer_mut() == &expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn config() {
+        let builder = PrinterBuilder::new(Vec::<u8>::new())
+            .num_panels(3)
+            .group_size(4)
+            .with_base(Base::Octal)
+            .endianness(Endianness::Little)
+            .character_table(CharacterTable::CP437)
+            .with_border_style(BorderStyle::Ascii)
+            .show_color(false)
+            .show_char_panel(false)
+            .enable_squeezing(false);
+        let config = builder.to_config();
+        assert_eq!(config.panels, 3);
+        assert_eq!(config.base, Base::Octal);
+        assert!(!config.squeeze);
+        assert_eq!(
+            PrinterBuilder::from_config(Vec::<u8>::new(), config).to_config(),
+            config
+        );
+        assert_eq!(
+            PrinterBuilder::new(Vec::<u8>::new()).to_config(),
+            PrinterConfig::default()
+        );
+    }
+
+    #[test]
+    fn header() {
+        let header = DumpHeader {
+            name: Some("a/very/long/path/to/the/input/file.bin".into()),
+            size: Some(16),
+            modified: None,
+            range: Some(0..3),
+        };
+        let print = |border_style| {
+            let mut output = vec![];
+            PrinterBuilder::new(&mut output)
+                .show_color(false)
+                .num_panels(1)
+                .with_border_style(border_style)
+                .header(header.clone())
+                .build()
+                .print_all(&b"abc"[..])
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        assert_eq!(
+            print(BorderStyle::Unicode),
+            "┌───────────────────────────────────────────┐\n\
+             │ a/very/long/path/to/the/input/file.bin, … │\n\
+             ├────────┬─────────────────────────┬────────┤\n\
+             │00000000│ 61 62 63                │abc     │\n\
+             └────────┴─────────────────────────┴────────┘\n"
+        );
+        assert_eq!(
+            print(BorderStyle::Ascii),
+            "+-------------------------------------------+\n\
+             | a/very/long/path/to/the/input/file.bin... |\n\
+             +--------+-------------------------+--------+\n\
+             |00000000| 61 62 63                |abc     |\n\
+             +--------+-------------------------+--------+\n"
+        );
+        assert_eq!(
+            print(BorderStyle::None),
+            "  a/very/long/path/to/the/input/file.bin, …  \n\
+             \x2000000000  61 62 63                 abc      \n"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn config_serde() {
+        let config = PrinterConfig {
+            panels: 4,
+            character_table: CharacterTable::CP1047,
+            ..PrinterConfig::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"panels":4,"group_size":1,"base":"hexadecimal","endianness":"big","character_table":"codepage-1047","border_style":"unicode","show_color":true,"show_char_panel":true,"show_position_panel":true,"squeeze":true}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<PrinterConfig>(&json).unwrap(),
+            config
+        );
+
+        let config: PrinterConfig =
+            serde_json::from_str(r#"{"base":"binary","show_color":false}"#).unwrap();
+        assert_eq!(config.base, Base::Binary);
+        assert!(!config.show_color);
+        assert_eq!(config.panels, 2);
+    }
 }
@@ -1,13 +1,15 @@
+use std::borrow::Cow;
 use std::env;
-use std::ffi::OsStr;
-use std::fs::File;
+use std::ffi::{OsStr, OsString};
+use std::fs::{self, File};
 use std::io::{self, prelude::*, BufWriter, IsTerminal, SeekFrom, StdoutLock};
 use std::num::{NonZeroI64, NonZeroU64};
 use std::path::{Path, PathBuf};
 use std::process::{Child, ChildStdin, Command, Stdio};
+use std::time::UNIX_EPOCH;
 
 use clap::builder::ArgPredicate;
-use clap::{ArgAction, Parser, ValueEnum};
+use clap::{ArgAction, CommandFactory, Parser, ValueEnum};
 
 use anyhow::{anyhow, Context, Result};
 
@@ -15,9 +17,12 @@ use const_format::formatcp;
 
 use thiserror::Error as ThisError;
 
-use terminal_size::terminal_size;
+use terminal_size::{terminal_size, Height};
 
-use hexyl::{Base, BorderStyle, CharacterTable, Endianness, Input, PrinterBuilder};
+use hexyl::{
+    Base, BorderStyle, ByteCategory, CharacterTable, ConfigError, DumpHeader, DumpSummary,
+    Endianness, Input, Layout, OutputFormat, Printer, PrinterBuilder, Reverser, MAX_PANELS,
+};
 
 #[cfg(test)]
 mod tests;
@@ -47,14 +52,16 @@ Since the terminal width may not be an evenly divisible by the width per hex dat
                                         will use the greatest number of hex data panels that can \
                                         fit in the requested width but still leave some space to \
                                         the right.
-Cannot be used with other width-setting options.";
+Overrides '--panels', and the other way around, whichever comes last wins.";
 
 #[derive(Debug, Parser)]
 #[command(version, about, max_term_width(90))]
 struct Opt {
-    /// The file to display. If no FILE argument is given, read from STDIN.
+    /// The files to display. If no FILE argument is given, or if it is '-',
+    /// read from STDIN. Multiple files are dumped one after the other, each
+    /// with a header of its name.
     #[arg(value_name("FILE"))]
-    file: Option<PathBuf>,
+    files: Vec<PathBuf>,
 
     #[arg(
         help(LENGTH_HELP_TEXT),
@@ -78,12 +85,42 @@ struct Opt {
     )]
     block_size: String,
 
+    /// Keep going when the input can't be read, like 'dd conv=noerror'. The
+    /// input is read in blocks of '--block-size' bytes, and every block that
+    /// can't be read is skipped and shown as unreadable. Needs a seekable
+    /// input. The exit status is still an error if anything was skipped.
+    #[arg(long)]
+    continue_on_error: bool,
+
+    /// Always read the input. Otherwise, files with at least 1 MiB to dump
+    /// are dumped from a memory map, which is faster.
+    #[arg(long)]
+    no_mmap: bool,
+
+    /// Render the dump on N threads, or on one thread per CPU with 0. The
+    /// output is the same, but large inputs are dumped faster. The input is
+    /// read in batches of a few hundred KiB per thread, so the first rows of a
+    /// slow input appear later. '--continue-on-error' always uses one thread.
+    #[arg(long, default_value("1"), value_name("N"))]
+    threads: usize,
+
+    /// Print a summary of the dump to STDERR afterwards: the number of bytes
+    /// and rows, the offset after the last byte and the number of bytes of
+    /// every category.
+    #[arg(long)]
+    summary: bool,
+
     /// Displays all input data. Otherwise any number of groups of output lines
     /// which would be identical to the preceding group of lines, are replaced
     /// with a line comprised of a single asterisk.
-    #[arg(short('v'), long)]
+    #[arg(short('v'), long, overrides_with("squeeze"))]
     no_squeezing: bool,
 
+    /// Squeeze repeated lines in the xxd layout too, like 'xxd -a'. The other
+    /// layouts squeeze them by default.
+    #[arg(long, overrides_with("no_squeezing"))]
+    squeeze: bool,
+
     /// When to use colors.
     #[arg(
         long,
@@ -104,6 +141,12 @@ struct Opt {
     )]
     border: BorderStyle,
 
+    /// Show the name, size and modification time of the file and the
+    /// positions of the dumped bytes in a row inside the top border. Only
+    /// the hexyl layout and HTML have one.
+    #[arg(long)]
+    header: bool,
+
     /// Display output with --no-characters, --no-position, --border=none, and
     /// --color=never.
     #[arg(long)]
@@ -144,7 +187,7 @@ struct Opt {
     /// display the maximum number of hex data panels based on the current
     /// terminal width. By default, hexyl will show two panels, unless the
     /// terminal is not wide enough for that.
-    #[arg(long, value_name("N"))]
+    #[arg(long, value_name("N"), overrides_with("terminal_width"))]
     panels: Option<String>,
 
     /// Number of bytes/octets that should be grouped together. You can use the
@@ -179,16 +222,61 @@ struct Opt {
         help(TERMINAL_WIDTH_HELP_TEXT),
         long,
         value_name("N"),
-        conflicts_with("panels")
+        overrides_with("panels")
     )]
     terminal_width: Option<NonZeroU64>,
 
-    /// Spawn a pager to hold the output
-    ///
-    /// The pager command is set by the environment variable HEXYL_PAGER or PAGER, or defaults to
-    /// `less`.
-    #[arg(short, long)]
+    /// When to show the output in a pager. The auto-mode only uses a pager if
+    /// the output goes to an interactive terminal and doesn't fit on the
+    /// screen. Unless all inputs are regular files, it passes the output to
+    /// the pager right away, which should quit if it fits. It never uses a
+    /// pager while reading the input from the terminal. The pager command is
+    /// set by the environment variable HEXYL_PAGER or PAGER, or defaults to
+    /// 'less'.
+    #[arg(long, value_enum, default_value_t, value_name("WHEN"))]
+    paging: Paging,
+
+    /// An alias for '--paging=always'.
+    #[arg(short('p'), long, hide(true), overrides_with("paging"))]
     pager: bool,
+
+    /// Emulate the output format of another hex dump tool. All layouts other
+    /// than 'hexyl' ignore the display settings except for '--no-squeezing',
+    /// '--squeeze' and '--display-offset'.
+    #[arg(long, value_enum, default_value_t, value_name("LAYOUT"))]
+    layout: Layout,
+
+    /// What to produce from the input. Everything other than 'dump' ignores
+    /// the display settings.
+    #[arg(long, value_enum, default_value_t, value_name("FORMAT"))]
+    output_format: OutputFormat,
+
+    /// The number of bytes per line for the 'plain-hex' (default: 30) and the
+    /// array output formats (default: 12). Use 0 to disable line wrapping.
+    #[arg(long, value_name("N"))]
+    bytes_per_line: Option<usize>,
+
+    /// The name of the variable for the array output formats. Defaults to the
+    /// file name, or 'data' when reading from STDIN.
+    #[arg(long, value_name("NAME"))]
+    variable_name: Option<String>,
+
+    /// Convert a hex dump back into binary data. Understands the output of
+    /// hexyl, 'xxd' and 'hexdump -C'. Use '--base' and '--endianness' to
+    /// describe how a hexyl dump was created.
+    #[arg(short, long)]
+    reverse: bool,
+
+    /// Ignore the config file and the HEXYL_OPTS environment variable.
+    #[arg(long)]
+    no_config: bool,
+
+    /// Print the path of the config file and exit. Every line of the file holds
+    /// options like on the command line, lines starting with '#' are comments.
+    /// The options of the file come first, then those of HEXYL_OPTS, then
+    /// those of the command line, so that later ones override earlier ones.
+    #[arg(long)]
+    print_config_path: bool,
 }
 
 #[derive(Clone, Debug, Default, ValueEnum)]
@@ -207,6 +295,20 @@ enum ColorWhen {
     Force,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum Paging {
+    /// Only use a pager if the output goes to an interactive terminal and
+    /// doesn't fit on the screen.
+    #[default]
+    Auto,
+
+    /// Always use a pager.
+    Always,
+
+    /// Never use a pager.
+    Never,
+}
+
 #[derive(Clone, Debug, Default, ValueEnum)]
 enum GroupSize {
     /// Grouped together every byte/octet.
@@ -238,14 +340,163 @@ impl From<GroupSize> for u8 {
     }
 }
 
+/// The path of the config file, `$XDG_CONFIG_HOME/hexyl/config` or `~/.config/hexyl/config`.
+fn config_path() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .map(|dir| dir.join("hexyl").join("config"))
+}
+
+/// Splits the contents of a config file into arguments. Every line is split into words like by a
+/// shell, and lines starting with `#` are comments.
+fn parse_config(contents: &str) -> Result<Vec<String>> {
+    let mut args = vec![];
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        args.extend(shell_words::split(line).context(anyhow!("invalid line {:?}", line))?);
+    }
+    Ok(args)
+}
+
+/// The command line arguments, preceded by those of the config file and of `HEXYL_OPTS` unless
+/// `--no-config` is given.
+fn args() -> Result<Vec<OsString>> {
+    let args: Vec<OsString> = env::args_os().collect();
+    if args
+        .iter()
+        .skip(1)
+        .take_while(|arg| *arg != "--")
+        .any(|arg| arg == "--no-config")
+    {
+        return Ok(args);
+    }
+
+    let mut defaults = vec![];
+    if let Some(path) = config_path() {
+        match fs::read_to_string(&path) {
+            Ok(contents) => defaults.extend(parse_config(&contents).context(anyhow!(
+                "failed to parse the config file {}",
+                path.display()
+            ))?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(anyhow!(e)
+                    .context(format!("failed to read the config file {}", path.display())))
+            }
+        }
+    }
+    if let Some(opts) = env::var_os("HEXYL_OPTS") {
+        let opts = opts
+            .to_str()
+            .ok_or_else(|| anyhow!("HEXYL_OPTS is not valid UTF-8"))?;
+        defaults.extend(shell_words::split(opts).context("failed to parse HEXYL_OPTS")?);
+    }
+    Ok(merge_args(defaults, args))
+}
+
+/// Inserts the `defaults` into the command line `args`, leaving out every default option that
+/// is given again later, so that it is overridden instead of being an error.
+fn merge_args(defaults: Vec<String>, mut args: Vec<OsString>) -> Vec<OsString> {
+    let command = Opt::command();
+    let defaults = split_options(&command, defaults);
+    let given: Vec<String> = args
+        .iter()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy().into())
+        .collect();
+    let mut later_ids: Vec<_> = split_options(&command, given)
+        .into_iter()
+        .filter_map(|(id, _)| id)
+        .collect();
+
+    let mut kept = vec![];
+    for (id, option) in defaults.into_iter().rev() {
+        match id {
+            Some(id) if later_ids.contains(&id) => {}
+            Some(id) => {
+                later_ids.push(id);
+                kept.push(option);
+            }
+            None => kept.push(option),
+        }
+    }
+    let defaults = kept.into_iter().rev().flatten().map(OsString::from);
+    args.splice(1..1, defaults);
+    args
+}
+
+/// Splits `args` into options with their values, along with the ID of the option. Positional
+/// arguments have no ID, and clusters of short options are split into single options.
+fn split_options(command: &clap::Command, args: Vec<String>) -> Vec<(Option<String>, Vec<String>)> {
+    let takes_value = |arg: &clap::Arg| arg.get_action().takes_values();
+    let mut options = vec![];
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            options.push((None, std::iter::once(arg).chain(args).collect()));
+            break;
+        } else if let Some(long) = arg.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (long, None),
+            };
+            let found = command.get_arguments().find(|a| {
+                a.get_long() == Some(name)
+                    || a.get_all_aliases()
+                        .is_some_and(|aliases| aliases.contains(&name))
+            });
+            let id = found.map(|a| a.get_id().to_string());
+            let mut option = vec![arg.clone()];
+            if value.is_none() && found.is_some_and(takes_value) {
+                option.extend(args.next());
+            }
+            options.push((id, option));
+        } else if arg.len() > 1 && arg.starts_with('-') {
+            for (i, c) in arg.char_indices().skip(1) {
+                let found = command.get_arguments().find(|a| {
+                    a.get_short() == Some(c)
+                        || a.get_all_short_aliases()
+                            .is_some_and(|aliases| aliases.contains(&c))
+                });
+                let id = found.map(|a| a.get_id().to_string());
+                if found.is_some_and(takes_value) {
+                    let mut option = vec![format!("-{}", &arg[i..])];
+                    if i + c.len_utf8() == arg.len() {
+                        option.extend(args.next());
+                    }
+                    options.push((id, option));
+                    break;
+                }
+                options.push((id, vec![format!("-{c}")]));
+            }
+        } else {
+            options.push((None, vec![arg]));
+        }
+    }
+    options
+}
+
 fn run() -> Result<()> {
-    let opt = Opt::parse();
+    let opt = Opt::parse_from(args()?);
+
+    if opt.print_config_path {
+        let path = config_path().ok_or_else(|| {
+            anyhow!("neither XDG_CONFIG_HOME nor HOME is set to find the config file")
+        })?;
+        println!("{}", path.display());
+        return Ok(());
+    }
 
     let stdin = io::stdin();
 
-    let mut reader = match opt.file {
-        Some(filename) => Input::File(File::open(filename)?),
-        None => Input::Stdin(stdin.lock()),
+    let files: Vec<&Path> = if opt.files.is_empty() {
+        vec![Path::new("-")]
+    } else {
+        opt.files.iter().map(PathBuf::as_path).collect()
     };
 
     if let Some(hex_number) = try_parse_as_hex_number(&opt.block_size) {
@@ -280,41 +531,22 @@ fn run() -> Result<()> {
         })
         .transpose()?;
 
-    let skip_offset = if let Some(ByteOffset { kind, value }) = skip_arg {
-        let value = value.into_inner();
-        reader
-            .seek(match kind {
-                ByteOffsetKind::ForwardFromBeginning | ByteOffsetKind::ForwardFromLastOffset => {
-                    SeekFrom::Current(value)
-                }
-                ByteOffsetKind::BackwardFromEnd => SeekFrom::End(value.checked_neg().unwrap()),
-            })
-            .map_err(|_| {
-                anyhow!(
-                    "Failed to jump to the desired input position. \
-                     This could be caused by a negative offset that is too large or by \
-                     an input that is not seek-able (e.g. if the input comes from a pipe)."
-                )
-            })?
-    } else {
-        0
-    };
-
     let parse_byte_count = |s| -> Result<u64> {
         Ok(parse_byte_offset(s, block_size)?
             .assume_forward_offset_from_start()?
             .into())
     };
 
-    let mut reader = if let Some(ref length) = opt.length {
-        let length = parse_byte_count(length).context(anyhow!(
-            "failed to parse `--length` arg {:?} as byte count",
-            length
-        ))?;
-        Box::new(reader.take(length))
-    } else {
-        reader.into_inner()
-    };
+    let length = opt
+        .length
+        .as_ref()
+        .map(|length| {
+            parse_byte_count(length).context(anyhow!(
+                "failed to parse `--length` arg {:?} as byte count",
+                length
+            ))
+        })
+        .transpose()?;
 
     let no_color = std::env::var_os("NO_COLOR").is_some();
     let show_color = match opt.color {
@@ -334,7 +566,11 @@ fn run() -> Result<()> {
 
     let border_style = opt.border;
 
-    let &squeeze = &!opt.no_squeezing;
+    // plain 'xxd' doesn't squeeze, only 'xxd -a' does
+    let squeeze = match opt.layout {
+        Layout::Xxd => opt.squeeze,
+        _ => !opt.no_squeezing,
+    };
 
     let show_char_panel = !opt.no_characters && !opt.plain;
 
@@ -352,11 +588,7 @@ fn run() -> Result<()> {
         } else {
             ((8 / group_size) * (base_digits * group_size + 1)) + 2
         };
-        if (terminal_width - offset) / col_width < 1 {
-            1
-        } else {
-            (terminal_width - offset) / col_width
-        }
+        ((terminal_width - offset) / col_width).clamp(1, MAX_PANELS)
     };
 
     let base = if let Ok(base_num) = opt.base.parse::<u8>() {
@@ -419,18 +651,62 @@ fn run() -> Result<()> {
 
     let character_table = opt.character_table;
 
+    if opt.reverse {
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        for file in &files {
+            let (mut input, _) = open_input(file, &stdin, skip_arg.as_ref())?;
+            Reverser::new()
+                .with_base(base)
+                .endianness(endianness)
+                .reverse(
+                    io::BufReader::new(limit_input(&mut input, length)),
+                    &mut stdout,
+                )
+                .context("failed to reverse the hex dump")?;
+        }
+        return Ok(());
+    }
+
+    let paging = if opt.pager {
+        Paging::Always
+    } else {
+        opt.paging
+    };
+
     let stdout = io::stdout();
-    let mut output = if opt.pager {
+    let mut output = if paging == Paging::Always {
         if !stdout.is_terminal() {
             return Err(anyhow!("can't use a pager when stdout is not a TTY"));
         }
-        let pager = env::var_os("HEXYL_PAGER")
-            .or_else(|| env::var_os("PAGER"))
-            .unwrap_or_else(|| "less".into());
-        spawn_pager(&pager)?
+        spawn_pager(pager_command()?)?
     } else if stdout.is_terminal() {
-        // when writing to a terminal, use the default line-buffered stdout
-        Output::Stdout(stdout.lock())
+        match terminal_size() {
+            // a pager would read the keys typed into the terminal that are meant for the input
+            Some(_)
+                if paging == Paging::Auto
+                    && stdin.is_terminal()
+                    && files.iter().any(|&file| file == Path::new("-")) =>
+            {
+                Output::Stdout(stdout.lock())
+            }
+            // the output of an input that may never end, like a pipe from 'tail -f', can't be
+            // held back until it is known to fit, so it goes to the pager right away
+            Some(_)
+                if paging == Paging::Auto && !files.iter().all(|file| is_regular_file(file)) =>
+            {
+                spawn_pager(pager_command()?)?
+            }
+            Some((_, Height(height))) if paging == Paging::Auto => Output::Undecided {
+                stdout: stdout.lock(),
+                buffer: vec![],
+                lines: 0,
+                height: height.into(),
+                pager: pager_command()?,
+            },
+            // when writing to a terminal, use the default line-buffered stdout
+            _ => Output::Stdout(stdout.lock()),
+        }
     } else {
         // when writing elsewhere, try to re-open stdout as a regular file and apply block
         // buffering to reduce overhead. If we're not unix or if opening /dev/stdout fails,
@@ -447,40 +723,289 @@ fn run() -> Result<()> {
             .unwrap_or_else(|| Output::Stdout(stdout.lock()))
     };
 
-    let mut printer = PrinterBuilder::new(&mut output)
-        .show_color(show_color)
-        .show_char_panel(show_char_panel)
-        .show_position_panel(show_position_panel)
-        .with_border_style(border_style)
-        .enable_squeezing(squeeze)
-        .num_panels(panels)
-        .group_size(group_size)
-        .with_base(base)
-        .endianness(endianness)
-        .character_table(character_table)
-        .build();
-    printer.display_offset(skip_offset + display_offset);
-    printer.print_all(&mut reader).map_err(|e| anyhow!(e))?;
-
-    output.wait()?;
+    let dump = |output: &mut Output, file: &Path, input: &mut Input, skip_offset: u64| {
+        let is_array = matches!(
+            opt.output_format,
+            OutputFormat::CArray
+                | OutputFormat::RustArray
+                | OutputFormat::PythonArray
+                | OutputFormat::GoArray
+        );
+        let variable_name = opt.variable_name.clone().or_else(|| {
+            (is_array && file != Path::new("-"))
+                .then(|| file.file_name())
+                .flatten()
+                .map(|name| name.to_string_lossy().into_owned())
+        });
+        let mut builder = PrinterBuilder::new(output)
+            .show_color(show_color)
+            .show_char_panel(show_char_panel)
+            .show_position_panel(show_position_panel)
+            .with_border_style(border_style)
+            .enable_squeezing(squeeze)
+            .num_panels(panels)
+            .group_size(group_size)
+            .with_base(base)
+            .endianness(endianness)
+            .character_table(character_table)
+            .layout(opt.layout)
+            .output_format(opt.output_format)
+            .threads(opt.threads);
+        if let Some(bytes_per_line) = opt.bytes_per_line {
+            builder = builder.bytes_per_line(bytes_per_line);
+        }
+        if let Some(variable_name) = variable_name {
+            builder = builder.variable_name(variable_name);
+        }
+        if opt.header {
+            builder = builder.header(dump_header(file, input, skip_offset, length));
+        }
+        let mut printer = builder.try_build()?;
+        let offset = skip_offset + display_offset;
+        printer.display_offset(offset);
+        let skipped = if opt.continue_on_error {
+            // the length is applied by reading in blocks instead
+            let block_size = block_size.into_inner() as u64;
+            print_continuing_on_error(&mut printer, input, offset, length, block_size)?
+        } else {
+            match input {
+                Input::File(file) if !opt.no_mmap => printer.print_file(file, length),
+                _ => printer.print_all(limit_input(input, length)),
+            }
+            .map_err(|e| anyhow!(e))?;
+            0
+        };
+        Ok::<_, anyhow::Error>((printer.summary().clone(), skipped))
+    };
+
+    let mut summaries = vec![];
+    let mut skipped = 0;
+    let mut failed = 0;
+    let mut result = Ok(());
+    // the files are separated by a blank line, but only after the header of one was printed
+    let mut printed_header = false;
+    for file in &files {
+        let file_result =
+            open_input(file, &stdin, skip_arg.as_ref()).and_then(|(mut input, skip_offset)| {
+                if files.len() > 1 {
+                    if printed_header {
+                        writeln!(output)?;
+                    }
+                    writeln!(output, "==> {} <==", input_name(file))?;
+                    printed_header = true;
+                }
+                dump(&mut output, file, &mut input, skip_offset)
+            });
+        match file_result {
+            Ok((summary, file_skipped)) => {
+                summaries.push((file, summary));
+                skipped += file_skipped;
+            }
+            // a single file fails like before, and errors that aren't caused by the file stop
+            Err(e) if files.len() == 1 || is_broken_pipe(&e) || e.is::<ConfigError>() => {
+                result = Err(e);
+                break;
+            }
+            Err(e) => {
+                // show the error after the output that was printed before it
+                let _ = output.flush_before_error();
+                eprintln!(
+                    "Error: {:?}",
+                    e.context(format!("failed to dump {}", input_name(file)))
+                );
+                failed += 1;
+            }
+        }
+    }
+
+    // show the output that was printed before an error, too
+    let waited = output.wait();
+    result?;
+    waited?;
+
+    if opt.summary {
+        for (file, summary) in summaries {
+            if files.len() > 1 {
+                eprintln!("{}: {}", input_name(file), format_summary(&summary));
+            } else {
+                eprintln!("{}", format_summary(&summary));
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow!(
+            "failed to dump {failed} of the {} files",
+            files.len()
+        ));
+    }
+
+    if skipped > 0 {
+        return Err(anyhow!("skipped {skipped} unreadable bytes of the input"));
+    }
 
     Ok(())
 }
 
+/// Opens `file`, or STDIN for `-`, and skips to the `--skip` offset, which is returned.
+fn open_input<'a>(
+    file: &Path,
+    stdin: &'a io::Stdin,
+    skip: Option<&ByteOffset>,
+) -> Result<(Input<'a>, u64)> {
+    let mut input = if file == Path::new("-") {
+        Input::Stdin(stdin.lock())
+    } else {
+        Input::File(File::open(file)?)
+    };
+
+    let skip_offset = if let Some(&ByteOffset { kind, value }) = skip {
+        let value = value.into_inner();
+        input
+            .seek(match kind {
+                ByteOffsetKind::ForwardFromBeginning | ByteOffsetKind::ForwardFromLastOffset => {
+                    SeekFrom::Current(value)
+                }
+                ByteOffsetKind::BackwardFromEnd => SeekFrom::End(value.checked_neg().unwrap()),
+            })
+            .map_err(|_| {
+                anyhow!(
+                    "Failed to jump to the desired input position. \
+                     This could be caused by a negative offset that is too large or by \
+                     an input that is not seek-able (e.g. if the input comes from a pipe)."
+                )
+            })?
+    } else {
+        0
+    };
+    Ok((input, skip_offset))
+}
+
+/// The header of `--header` for `input`, which is dumped from `skip_offset` on.
+fn dump_header(file: &Path, input: &Input, skip_offset: u64, length: Option<u64>) -> DumpHeader {
+    // the size of e.g. a pipe is meaningless
+    let metadata = input.metadata().ok().filter(|metadata| metadata.is_file());
+    let size = metadata.as_ref().map(|metadata| metadata.len());
+    let modified = metadata
+        .and_then(|metadata| metadata.modified().ok())
+        .map(|time| match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs() as i64),
+        });
+    let range = size.map(|size| {
+        let start = skip_offset.min(size);
+        let end = length.map_or(size, |length| start.saturating_add(length).min(size));
+        start..end
+    });
+    DumpHeader {
+        name: Some(input_name(file).into_owned()),
+        size,
+        modified,
+        range,
+    }
+}
+
+/// Reads at most `length` bytes of `input`, if there is a `--length`.
+fn limit_input<'a>(input: &'a mut Input, length: Option<u64>) -> Box<dyn Read + 'a> {
+    match length {
+        Some(length) => Box::new(input.take(length)),
+        None => Box::new(input),
+    }
+}
+
+/// The name of `file` in the headers of multiple files, like `head` shows it.
+fn input_name(file: &Path) -> Cow<'_, str> {
+    if file == Path::new("-") {
+        "standard input".into()
+    } else {
+        file.to_string_lossy()
+    }
+}
+
+fn is_broken_pipe(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<io::Error>()
+        .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+}
+
+fn format_summary(summary: &DumpSummary) -> String {
+    let mut line = format!(
+        "{} bytes, {} rows printed, {} rows squeezed, end offset {:#x}",
+        summary.bytes(),
+        summary.rows_printed(),
+        summary.rows_squeezed(),
+        summary.end_offset()
+    );
+    if summary.unreadable_bytes() > 0 {
+        line += &format!(", {} unreadable bytes", summary.unreadable_bytes());
+    }
+    for category in ByteCategory::ALL {
+        line += &format!(
+            ", {}: {}",
+            category.name(),
+            summary.category_count(category)
+        );
+    }
+    line
+}
+
+/// Prints up to `length` bytes of the input like `dd conv=noerror`: the input is read in blocks,
+/// and a block that can't be read is skipped and shown as unreadable. `offset` is the position of
+/// the first byte, for error messages. Returns the number of skipped bytes.
+fn print_continuing_on_error<Writer: Write>(
+    printer: &mut Printer<Writer>,
+    input: &mut Input,
+    mut offset: u64,
+    length: Option<u64>,
+    block_size: u64,
+) -> Result<u64> {
+    let mut buf = vec![0; block_size as usize];
+    let mut remaining = length.unwrap_or(u64::MAX);
+    let mut skipped = 0;
+    while remaining > 0 {
+        let len = remaining.min(block_size) as usize;
+        match input.read(&mut buf[..len]) {
+            Ok(0) => break,
+            Ok(n) => {
+                printer.write_all(&buf[..n])?;
+                remaining -= n as u64;
+                offset += n as u64;
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => {
+                if input.seek(SeekFrom::Current(len as i64)).is_err() {
+                    printer.finish()?;
+                    return Err(anyhow!(e).context(format!(
+                        "failed to read the input at offset {offset:#x}, \
+                         which can't be skipped because the input is not seekable"
+                    )));
+                }
+                printer.write_unreadable(len as u64)?;
+                remaining -= len as u64;
+                skipped += len as u64;
+                offset += len as u64;
+            }
+        }
+    }
+    printer.finish()?;
+    Ok(skipped)
+}
+
 fn main() {
     let result = run();
 
     if let Err(err) = result {
-        if let Some(io_error) = err.downcast_ref::<io::Error>() {
-            if io_error.kind() == ::std::io::ErrorKind::BrokenPipe {
-                std::process::exit(0);
-            }
+        if is_broken_pipe(&err) {
+            std::process::exit(0);
         }
         eprintln!("Error: {err:?}");
         std::process::exit(1);
     }
 }
 
+/// The number of bytes that `--paging=auto` buffers at most before it uses the pager, in case of
+/// long lines.
+const MAX_UNDECIDED_BUFFER: usize = 64 * 1024;
+
 enum Output<'a> {
     Stdout(StdoutLock<'a>),
     Pager {
@@ -488,13 +1013,40 @@ enum Output<'a> {
         pipe: BufWriter<ChildStdin>,
     },
     File(BufWriter<File>),
+    /// Buffers the output of `--paging=auto` for regular files until it is known to fit on the
+    /// screen, or switches to the pager once it has `height` lines.
+    Undecided {
+        stdout: StdoutLock<'a>,
+        buffer: Vec<u8>,
+        lines: usize,
+        height: usize,
+        pager: Vec<String>,
+    },
 }
 
 impl<'a> Output<'a> {
+    /// Writes out the output that was printed so far, before an error message. The buffered
+    /// output of `--paging=auto` and the rest of the output go to the terminal then, because a
+    /// pager would hide the error.
+    fn flush_before_error(&mut self) -> io::Result<()> {
+        if let Self::Undecided { stdout, buffer, .. } = self {
+            stdout.write_all(buffer)?;
+            *self = Self::Stdout(io::stdout().lock());
+        }
+        self.flush()
+    }
+
     fn wait(mut self) -> Result<(), anyhow::Error> {
         let _ = self.flush();
         match self {
             Self::Stdout(_) | Self::File(_) => Ok(()),
+            Self::Undecided {
+                mut stdout, buffer, ..
+            } => {
+                stdout.write_all(&buffer)?;
+                stdout.flush()?;
+                Ok(())
+            }
             Self::Pager { mut child, pipe } => {
                 // we've taken the stdin handle from the Child, so we have to close it ourselves
                 // before waiting.
@@ -516,6 +1068,25 @@ impl<'a> Write for Output<'a> {
             Self::Stdout(stdout) => stdout.write(buf),
             Self::Pager { pipe, .. } => pipe.write(buf),
             Self::File(file) => file.write(buf),
+            Self::Undecided {
+                buffer,
+                lines,
+                height,
+                pager,
+                ..
+            } => {
+                buffer.extend_from_slice(buf);
+                *lines += buf.iter().filter(|&&b| b == b'\n').count();
+                // the prompt of the shell needs a line, too
+                if *lines >= *height || buffer.len() > MAX_UNDECIDED_BUFFER {
+                    let buffer = std::mem::take(buffer);
+                    let mut pager = spawn_pager(std::mem::take(pager))
+                        .map_err(|e| io::Error::other(format!("{e:#}")))?;
+                    pager.write_all(&buffer)?;
+                    *self = pager;
+                }
+                Ok(buf.len())
+            }
         }
     }
 
@@ -524,22 +1095,63 @@ impl<'a> Write for Output<'a> {
             Self::Stdout(stdout) => stdout.flush(),
             Self::Pager { pipe, .. } => pipe.flush(),
             Self::File(file) => file.flush(),
+            // the buffered output is written once it is known whether it needs the pager
+            Self::Undecided { .. } => Ok(()),
         }
     }
 }
 
-fn spawn_pager(pager: &OsStr) -> Result<Output<'static>, anyhow::Error> {
-    let mut cmd = Command::new(pager);
-    cmd.stdin(Stdio::piped());
-    if Path::new(pager).file_stem().map(|s| s.to_str()) == Some(Some("less")) {
-        // this is buggy on less versions before 530, but include this option unconditionally for
-        // simplicity and assume a new enough system
-        cmd.arg("--quit-if-one-screen");
+/// The pager command of HEXYL_PAGER or PAGER, or `less`.
+fn pager_command() -> Result<Vec<String>> {
+    let pager = env::var_os("HEXYL_PAGER")
+        .or_else(|| env::var_os("PAGER"))
+        .unwrap_or_else(|| "less".into());
+    let pager = pager
+        .to_str()
+        .ok_or_else(|| anyhow!("the pager command is not valid UTF-8"))?;
+    parse_pager_command(pager)
+}
+
+/// Splits a pager command into the program and its arguments like a shell. `less` gets the
+/// options to show colors and to quit if the output fits on the screen, before the arguments of
+/// the command so that they can be overridden.
+fn parse_pager_command(pager: &str) -> Result<Vec<String>> {
+    let mut command = shell_words::split(pager)
+        .context(anyhow!("failed to parse the pager command {:?}", pager))?;
+    let program = command
+        .first()
+        .ok_or_else(|| anyhow!("the pager command is empty"))?;
+    if Path::new(program).file_stem() == Some(OsStr::new("less")) {
+        // --quit-if-one-screen is buggy on less versions before 530, but include this option
+        // unconditionally for simplicity and assume a new enough system
+        command.splice(
+            1..1,
+            ["--RAW-CONTROL-CHARS", "--quit-if-one-screen"].map(String::from),
+        );
     }
+    Ok(command)
+}
+
+/// Whether `file` is a regular file, whose input ends by itself. On unix, STDIN can be a
+/// redirected file, too.
+fn is_regular_file(file: &Path) -> bool {
+    let path = if file != Path::new("-") {
+        file
+    } else if cfg!(unix) {
+        Path::new("/dev/stdin")
+    } else {
+        return false;
+    };
+    fs::metadata(path).is_ok_and(|metadata| metadata.is_file())
+}
+
+fn spawn_pager(command: Vec<String>) -> Result<Output<'static>, anyhow::Error> {
+    let mut cmd = Command::new(&command[0]);
+    cmd.args(&command[1..]).stdin(Stdio::piped());
 
     let mut child = cmd
         .spawn()
-        .with_context(|| format!("Failed to spawn pager '{}'", pager.to_string_lossy()))?;
+        .with_context(|| format!("Failed to spawn pager '{}'", command[0]))?;
     let pipe = BufWriter::new(child.stdin.take().expect("child is missing stdin handle"));
 
     Ok(Output::Pager { child, pipe })
@@ -0,0 +1,429 @@
+use std::fs::File;
+use std::io::{self, BufRead, Read, Seek, SeekFrom};
+
+use memmap2::{Mmap, MmapOptions};
+
+use crate::io::Write;
+use crate::{DumpSummary, Printer};
+
+/// Files with at least this many bytes to dump are mapped by [`Printer::print_file`].
+pub const MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+/// The number of mapped bytes that are dumped at once, after checking that the file still has
+/// them.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+impl<Writer: Write> Printer<Writer> {
+    /// Prints a dump of `file` from its current position on, of at most `length` bytes, like
+    /// [`Printer::print_all`]. If it is a regular file with at least [`MMAP_THRESHOLD`] bytes to
+    /// dump, they are dumped from a memory map of the file instead of being read, which is
+    /// faster for large files. Afterwards, the position of `file` is after the dumped bytes.
+    ///
+    /// Pipes, devices and files that don't know their size, like the ones in `/proc`, are read.
+    /// A file that shrinks while it is dumped ends at its new end, because the size of the file
+    /// is checked before every megabyte of the map. If it shrinks within such a megabyte, the
+    /// bytes that it doesn't have anymore are dumped as zeros and the dump fails with an error
+    /// of the kind [`io::ErrorKind::UnexpectedEof`] afterwards. On unix, a handler of `SIGBUS`
+    /// is installed for that on the first call, which passes the signals of other addresses on
+    /// to the previous handler.
+    pub fn print_file(&mut self, file: &mut File, length: Option<u64>) -> io::Result<DumpSummary> {
+        let Some(mut reader) = MappedReader::new(file, length) else {
+            return match length {
+                Some(length) => self.print_all(file.take(length)),
+                None => self.print_all(file),
+            };
+        };
+        #[cfg(feature = "parallel")]
+        let buffered = self.has_rows() && !self.is_parallel();
+        #[cfg(not(feature = "parallel"))]
+        let buffered = self.has_rows();
+        let result = if buffered {
+            self.dumper.print_buffered(&mut reader)
+        } else {
+            self.print_all(&mut reader)
+        };
+        let position = reader.start + reader.pos as u64;
+        drop(reader);
+        file.seek(SeekFrom::Start(position))?;
+        result
+    }
+}
+
+/// Reads a file from a memory map, in chunks that the file still has.
+struct MappedReader<'a> {
+    file: &'a File,
+    /// Recovers from the accesses to the pages that the file doesn't have anymore. It is dropped
+    /// before the map, so it never covers the addresses of another map.
+    #[cfg(unix)]
+    guard: sigbus::Guard,
+    map: Mmap,
+    /// The position of the map in the file.
+    start: u64,
+    /// The number of bytes of the map that are consumed.
+    pos: usize,
+    /// The end of the bytes of the map that the file had at the last check.
+    end: usize,
+}
+
+impl<'a> MappedReader<'a> {
+    /// Maps at most `length` bytes of `file` from its current position, or returns `None` if the
+    /// file is not a regular file, is too small, or can't be mapped.
+    fn new(mut file: &'a File, length: Option<u64>) -> Option<Self> {
+        let metadata = file.metadata().ok()?;
+        if !metadata.is_file() {
+            return None;
+        }
+        let start = file.stream_position().ok()?;
+        let mut len = metadata.len().saturating_sub(start);
+        if let Some(length) = length {
+            len = len.min(length);
+        }
+        if len < MMAP_THRESHOLD {
+            return None;
+        }
+        let len = usize::try_from(len).ok()?;
+        // SAFETY: on unix, the guard turns the pages that a truncated file doesn't have anymore
+        // into zeros instead of raising SIGBUS, and Windows doesn't truncate mapped files
+        let map = unsafe { MmapOptions::new().offset(start).len(len).map(file) }.ok()?;
+        #[cfg(unix)]
+        let guard = sigbus::Guard::new(&map)?;
+        #[cfg(unix)]
+        let _ = map.advise(memmap2::Advice::Sequential);
+        Some(MappedReader {
+            file,
+            #[cfg(unix)]
+            guard,
+            map,
+            start,
+            pos: 0,
+            end: 0,
+        })
+    }
+}
+
+impl BufRead for MappedReader<'_> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos == self.end {
+            #[cfg(unix)]
+            if self.guard.is_truncated() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "the file was truncated while it was dumped",
+                ));
+            }
+            // the file may have been truncated since it was mapped, and the bytes that it doesn't
+            // have anymore shouldn't be dumped
+            let size = self.file.metadata()?.len();
+            let available = size.saturating_sub(self.start);
+            let available = usize::try_from(available).unwrap_or(usize::MAX);
+            self.end = (self.pos + CHUNK_SIZE)
+                .min(self.map.len())
+                .min(available.max(self.pos));
+        }
+        Ok(&self.map[self.pos..self.end])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.end);
+    }
+}
+
+impl Read for MappedReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.fill_buf()?.read(buf)?;
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+/// Recovers from the `SIGBUS` of an access to a page of a map that the truncated file doesn't
+/// have anymore, by mapping a page of zeros in its place.
+#[cfg(unix)]
+mod sigbus {
+    use std::ptr;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::OnceLock;
+
+    use libc::{c_int, c_void, siginfo_t};
+
+    /// The number of maps that can be dumped at the same time. Other maps aren't used.
+    const SLOTS: usize = 16;
+
+    /// The addresses of a map that is dumped, or a free slot with a `start` of 0.
+    struct Slot {
+        start: AtomicUsize,
+        len: AtomicUsize,
+        truncated: AtomicBool,
+    }
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const FREE: Slot = Slot {
+        start: AtomicUsize::new(0),
+        len: AtomicUsize::new(0),
+        truncated: AtomicBool::new(false),
+    };
+
+    static MAPS: [Slot; SLOTS] = [FREE; SLOTS];
+
+    /// The handler of `SIGBUS` before ours.
+    static PREVIOUS: OnceLock<libc::sigaction> = OnceLock::new();
+
+    static PAGE_SIZE: AtomicUsize = AtomicUsize::new(0);
+
+    /// Installs the handler once, and returns whether it is installed.
+    fn install() -> bool {
+        static INSTALLED: OnceLock<bool> = OnceLock::new();
+        *INSTALLED.get_or_init(|| {
+            // SAFETY: the structs are initialized by sigemptyset and sigaction before their use
+            unsafe {
+                let page_size = libc::sysconf(libc::_SC_PAGESIZE);
+                if page_size <= 0 {
+                    return false;
+                }
+                PAGE_SIZE.store(page_size as usize, Ordering::Relaxed);
+
+                let mut previous: libc::sigaction = std::mem::zeroed();
+                if libc::sigaction(libc::SIGBUS, ptr::null(), &mut previous) != 0 {
+                    return false;
+                }
+                let _ = PREVIOUS.set(previous);
+
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = handle as extern "C" fn(c_int, *mut siginfo_t, *mut c_void)
+                    as libc::sighandler_t;
+                action.sa_flags = libc::SA_SIGINFO | libc::SA_ONSTACK;
+                libc::sigemptyset(&mut action.sa_mask);
+                libc::sigaction(libc::SIGBUS, &action, ptr::null_mut()) == 0
+            }
+        })
+    }
+
+    extern "C" fn handle(signal: c_int, info: *mut siginfo_t, context: *mut c_void) {
+        // SAFETY: the kernel passes a valid siginfo_t to a handler with SA_SIGINFO
+        let address = unsafe { (*info).si_addr() } as usize;
+        let page_size = PAGE_SIZE.load(Ordering::Relaxed);
+        for map in &MAPS {
+            let start = map.start.load(Ordering::Acquire);
+            let len = map.len.load(Ordering::Acquire);
+            if start == 0 || address < start || address - start >= len {
+                continue;
+            }
+            let page = address & !(page_size - 1);
+            // SAFETY: the page belongs to the map, whose pages are only read, and mmap is
+            // async-signal-safe in practice
+            let zeros = unsafe {
+                libc::mmap(
+                    page as *mut c_void,
+                    page_size,
+                    libc::PROT_READ,
+                    libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_FIXED,
+                    -1,
+                    0,
+                )
+            };
+            if zeros != libc::MAP_FAILED {
+                map.truncated.store(true, Ordering::Release);
+                // the access is repeated and reads the zeros
+                return;
+            }
+        }
+
+        match PREVIOUS.get() {
+            Some(previous)
+                if previous.sa_sigaction != libc::SIG_DFL
+                    && previous.sa_sigaction != libc::SIG_IGN =>
+            {
+                // SAFETY: the previous handler has the signature of its flags
+                unsafe {
+                    if previous.sa_flags & libc::SA_SIGINFO != 0 {
+                        let handler: extern "C" fn(c_int, *mut siginfo_t, *mut c_void) =
+                            std::mem::transmute(previous.sa_sigaction);
+                        handler(signal, info, context);
+                    } else {
+                        let handler: extern "C" fn(c_int) =
+                            std::mem::transmute(previous.sa_sigaction);
+                        handler(signal);
+                    }
+                }
+            }
+            // the access is repeated without a handler and terminates the process like before
+            _ => {
+                // SAFETY: resetting a handler to the default is async-signal-safe
+                unsafe { libc::signal(libc::SIGBUS, libc::SIG_DFL) };
+            }
+        }
+    }
+
+    /// Registers a map for the handler while it is dumped.
+    pub(super) struct Guard {
+        slot: &'static Slot,
+    }
+
+    impl Guard {
+        /// Returns `None` if the handler can't be installed or too many maps are dumped already.
+        pub(super) fn new(map: &[u8]) -> Option<Guard> {
+            if !install() {
+                return None;
+            }
+            let slot = MAPS.iter().find(|slot| {
+                slot.start
+                    .compare_exchange(
+                        0,
+                        map.as_ptr() as usize,
+                        Ordering::AcqRel,
+                        Ordering::Relaxed,
+                    )
+                    .is_ok()
+            })?;
+            slot.truncated.store(false, Ordering::Relaxed);
+            slot.len.store(map.len(), Ordering::Release);
+            Some(Guard { slot })
+        }
+
+        /// Whether the map had pages that the file doesn't have anymore.
+        pub(super) fn is_truncated(&self) -> bool {
+            self.slot.truncated.load(Ordering::Acquire)
+        }
+    }
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.slot.len.store(0, Ordering::Release);
+            self.slot.start.store(0, Ordering::Release);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use super::*;
+    use crate::PrinterBuilder;
+
+    fn temp_file(name: &str, len: usize) -> (File, Vec<u8>) {
+        let path = std::env::temp_dir().join(format!("hexyl-mmap-{}-{name}", std::process::id()));
+        let contents: Vec<u8> = (0..len).map(|i| (i / 1000) as u8).collect();
+        File::create(&path).unwrap().write_all(&contents).unwrap();
+        let file = File::options().read(true).write(true).open(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        (file, contents)
+    }
+
+    #[test]
+    fn same_output_as_print_all() {
+        let len = MMAP_THRESHOLD as usize * 3 + 123;
+        let (mut file, contents) = temp_file("same", len);
+        for (skip, length) in [(0, None), (5, Some(len as u64)), (17, Some(2_000_000))] {
+            let end = length.map_or(len, |l| skip + l as usize).min(len);
+            let mut expected = vec![];
+            PrinterBuilder::new(&mut expected)
+                .build()
+                .print_all(&contents[skip..end])
+                .unwrap();
+
+            let mut output = vec![];
+            file.seek(SeekFrom::Start(skip as u64)).unwrap();
+            let mut printer = PrinterBuilder::new(&mut output).build();
+            assert!(MappedReader::new(&file, length).is_some());
+            printer.print_file(&mut file, length).unwrap();
+            drop(printer);
+            assert!(output == expected);
+            assert_eq!(file.stream_position().unwrap(), end as u64);
+        }
+    }
+
+    #[test]
+    fn small_files_are_read() {
+        let (mut file, contents) = temp_file("small", 100);
+        assert!(MappedReader::new(&file, None).is_none());
+        let mut output = vec![];
+        PrinterBuilder::new(&mut output)
+            .build()
+            .print_file(&mut file, None)
+            .unwrap();
+        let mut expected = vec![];
+        PrinterBuilder::new(&mut expected)
+            .build()
+            .print_all(&contents[..])
+            .unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn truncated_file_ends_early() {
+        let len = CHUNK_SIZE * 3;
+        let (file, contents) = temp_file("truncated", len);
+        let mut reader = MappedReader::new(&file, None).unwrap();
+        let chunk = reader.fill_buf().unwrap();
+        assert_eq!(chunk, &contents[..CHUNK_SIZE]);
+        reader.consume(CHUNK_SIZE);
+
+        reader.file.set_len(CHUNK_SIZE as u64 + 10).unwrap();
+        assert_eq!(
+            reader.fill_buf().unwrap(),
+            &contents[CHUNK_SIZE..CHUNK_SIZE + 10]
+        );
+        reader.consume(10);
+        assert!(reader.fill_buf().unwrap().is_empty());
+    }
+
+    /// Truncates the file after the first row was written.
+    struct Truncating {
+        file: File,
+        len: u64,
+        output: Vec<u8>,
+    }
+
+    impl Write for Truncating {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.output.is_empty() {
+                self.file.set_len(self.len)?;
+            }
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn truncated_during_dump() {
+        let len = CHUNK_SIZE * 3;
+        let (mut file, contents) = temp_file("truncated-during-dump", len);
+        let mut writer = Truncating {
+            file: file.try_clone().unwrap(),
+            len: CHUNK_SIZE as u64 / 2,
+            output: vec![],
+        };
+        let mut printer = PrinterBuilder::new(&mut writer)
+            .show_position_panel(false)
+            .show_char_panel(false)
+            .num_panels(1)
+            .enable_squeezing(false)
+            .build();
+        let err = printer.print_file(&mut file, None).unwrap_err();
+        drop(printer);
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        // the bytes after the new end are zeros, and the dump stops after the first chunk
+        let rows: Vec<&[u8]> = writer.output.split(|&b| b == b'\n').collect();
+        let row = |bytes: &[u8]| {
+            let mut expected = vec![];
+            PrinterBuilder::new(&mut expected)
+                .show_position_panel(false)
+                .show_char_panel(false)
+                .num_panels(1)
+                .build()
+                .print_all(bytes)
+                .unwrap();
+            expected.split(|&b| b == b'\n').nth(1).unwrap().to_vec()
+        };
+        assert_eq!(rows[1], row(&contents[..8]));
+        assert_eq!(rows[CHUNK_SIZE / 8], row(&[0; 8]));
+        // with the borders and the empty rest after the last newline
+        assert_eq!(rows.len(), CHUNK_SIZE / 8 + 3);
+    }
+}
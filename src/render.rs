@@ -0,0 +1,781 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::io::{self, InputReader, Read};
+use crate::DumpSummary;
+
+/// The number of bytes that [`Dumper::print_all`] reads at once.
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// The number of bytes that a thread renders at once in a parallel dump.
+#[cfg(feature = "parallel")]
+const PARALLEL_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The number of chunks per thread that a parallel dump reads at once.
+#[cfg(feature = "parallel")]
+const PARALLEL_CHUNKS_PER_THREAD: usize = 4;
+
+/// Receives the structure of a dump from a [`Dumper`] and turns it into output.
+///
+/// For every row, [`Dumper`] calls `begin_row`, then `byte` for each byte of the row, then
+/// `partial_row` if the row is the incomplete last one, then `char_cell` for each byte of the row
+/// and finally `end_row`. Bytes that could not be read are `unreadable_byte` and `unreadable_char`
+/// instead. Indices are relative to the start of the row. Rows that stand for
+/// squeezed lines are a single `squeeze_marker` call instead.
+///
+/// Rows whose bytes could all be read are a single `row` call, which makes the calls above by
+/// default, so that a renderer can render them at once.
+pub trait Renderer {
+    /// Called before the first row of a non-empty dump.
+    fn begin_dump(&mut self) -> io::Result<()>;
+
+    /// Called instead of [`Renderer::begin_dump`] if there is no input at all.
+    fn empty_dump(&mut self) -> io::Result<()>;
+
+    /// Called after the last row, or after [`Renderer::empty_dump`].
+    fn end_dump(&mut self) -> io::Result<()>;
+
+    /// Starts a row at `offset`, which already includes the display offset.
+    fn begin_row(&mut self, offset: u64) -> io::Result<()>;
+
+    /// A byte in the hex panel.
+    fn byte(&mut self, index: usize, byte: u8) -> io::Result<()>;
+
+    /// Called after the bytes of the last row if it only has `len` bytes.
+    fn partial_row(&mut self, len: usize) -> io::Result<()>;
+
+    /// A byte in the character panel.
+    fn char_cell(&mut self, index: usize, byte: u8) -> io::Result<()>;
+
+    /// A byte in the hex panel that could not be read, see [`Dumper::push_unreadable`].
+    fn unreadable_byte(&mut self, index: usize) -> io::Result<()>;
+
+    /// A byte in the character panel that could not be read.
+    fn unreadable_char(&mut self, index: usize) -> io::Result<()>;
+
+    fn end_row(&mut self) -> io::Result<()>;
+
+    /// A row at `offset` whose bytes could all be read, which is the incomplete last one if it
+    /// is shorter than `row_len`.
+    fn row(&mut self, offset: u64, row: &[u8], row_len: usize) -> io::Result<()> {
+        self.begin_row(offset)?;
+        for (i, &b) in row.iter().enumerate() {
+            self.byte(i, b)?;
+        }
+        if row.len() < row_len {
+            self.partial_row(row.len())?;
+        }
+        for (i, &b) in row.iter().enumerate() {
+            self.char_cell(i, b)?;
+        }
+        self.end_row()
+    }
+
+    /// A complete row that stands for one or more squeezed rows starting at `offset`. The squeezed
+    /// rows are identical to the previous row.
+    fn squeeze_marker(&mut self, offset: u64) -> io::Result<()>;
+}
+
+/// A renderer whose rows can be rendered on other threads, by forks that render into buffers.
+#[cfg(feature = "parallel")]
+pub(crate) trait ForkRenderer: Renderer {
+    type Fork: Renderer + Send;
+
+    /// Whether the renderer can be forked.
+    fn can_fork(&self) -> bool;
+
+    /// A renderer with the same settings and state that renders into a buffer.
+    fn fork(&self) -> Self::Fork;
+
+    /// Takes the output of `fork` out of it.
+    fn take_output(fork: &mut Self::Fork) -> Vec<u8>;
+
+    /// Writes the output of a fork.
+    fn write_output(&mut self, output: &[u8]) -> io::Result<()>;
+
+    /// Continues with the state of `fork`, which rendered the rows before the next one.
+    fn resume(&mut self, fork: &Self::Fork);
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Squeezer {
+    Print,
+    Delete,
+    Ignore,
+    Disabled,
+}
+
+/// Splits the input into rows of `8 * panels` bytes, decides which rows are squeezed and drives a
+/// [`Renderer`] with the result.
+pub struct Dumper<R: Renderer> {
+    renderer: R,
+    idx: u64,
+    /// the buffer containing all the bytes in a line
+    line_buf: Vec<u8>,
+    /// The number of bytes of the current line in `line_buf`.
+    line_len: usize,
+    /// Which bytes of the current line could not be read.
+    unreadable: Vec<bool>,
+    /// The number of bytes of the current line that could not be read.
+    unreadable_len: usize,
+    /// Whether the dump has begun, which is with the first byte.
+    is_started: bool,
+    squeezer: Squeezer,
+    squeeze_byte: usize,
+    /// Whether the squeezed rows are unreadable, instead of repeating `squeeze_byte`.
+    squeeze_unreadable: bool,
+    display_offset: u64,
+    /// The number of panels per row.
+    panels: u64,
+    /// The summary of the current dump, or of the last one if it has ended.
+    summary: DumpSummary,
+}
+
+impl<R: Renderer> Dumper<R> {
+    pub fn new(renderer: R, panels: u64) -> Self {
+        Dumper {
+            renderer,
+            idx: 0,
+            line_buf: vec![0x0; 8 * panels as usize],
+            line_len: 0,
+            unreadable: vec![false; 8 * panels as usize],
+            unreadable_len: 0,
+            is_started: false,
+            squeezer: Squeezer::Ignore,
+            squeeze_byte: 0x00,
+            squeeze_unreadable: false,
+            display_offset: 0,
+            panels,
+            summary: DumpSummary::new(0),
+        }
+    }
+
+    /// Whether to squeeze repeated rows of identical bytes into a single marker row.
+    pub fn enable_squeezing(mut self, enable: bool) -> Self {
+        self.squeezer = if enable {
+            Squeezer::Ignore
+        } else {
+            Squeezer::Disabled
+        };
+        self
+    }
+
+    pub fn display_offset(&mut self, display_offset: u64) -> &mut Self {
+        self.display_offset = display_offset;
+        self
+    }
+
+    /// The summary of the current dump, or of the last one if it has ended.
+    pub fn summary(&self) -> &DumpSummary {
+        &self.summary
+    }
+
+    pub(crate) fn set_summary(&mut self, summary: DumpSummary) {
+        self.summary = summary;
+    }
+
+    pub(crate) fn summary_mut(&mut self) -> &mut DumpSummary {
+        &mut self.summary
+    }
+
+    pub fn renderer(&self) -> &R {
+        &self.renderer
+    }
+
+    pub fn renderer_mut(&mut self) -> &mut R {
+        &mut self.renderer
+    }
+
+    pub fn into_renderer(self) -> R {
+        self.renderer
+    }
+
+    /// The bytes of the current row.
+    pub(crate) fn line(&self) -> &[u8] {
+        &self.line_buf
+    }
+
+    /// Whether the dump has begun and is not finished yet.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn is_started(&self) -> bool {
+        self.is_started
+    }
+
+    /// The offset of the next pushed byte, including the display offset.
+    pub(crate) fn position(&self) -> u64 {
+        self.idx + self.line_len as u64 + self.display_offset
+    }
+
+    fn render_row(&mut self, row: &[u8]) -> io::Result<()> {
+        let offset = self.idx + self.display_offset;
+        if self.squeezer == Squeezer::Print {
+            self.summary.add_squeezed_row();
+            return self.renderer.squeeze_marker(offset);
+        }
+        self.summary.add_printed_row();
+        let row_len = 8 * self.panels as usize;
+        if self.unreadable_len == 0 {
+            return self.renderer.row(offset, row, row_len);
+        }
+        self.renderer.begin_row(offset)?;
+        for (i, &b) in row.iter().enumerate() {
+            if self.unreadable[i] {
+                self.renderer.unreadable_byte(i)?;
+            } else {
+                self.renderer.byte(i, b)?;
+            }
+        }
+        if row.len() < row_len {
+            self.renderer.partial_row(row.len())?;
+        }
+        for (i, &b) in row.iter().enumerate() {
+            if self.unreadable[i] {
+                self.renderer.unreadable_char(i)?;
+            } else {
+                self.renderer.char_cell(i, b)?;
+            }
+        }
+        self.renderer.end_row()
+    }
+
+    /// Loop through the given `Reader`, rendering until the `Reader` buffer
+    /// is exhausted.
+    ///
+    /// If reading fails, the dump ends with the bytes that were read so far, if any, and the error
+    /// is returned with the offset of the first unreadable byte, see [`io::ReadError`].
+    pub fn print_all<Reader: Read>(&mut self, reader: Reader) -> io::Result<DumpSummary> {
+        let mut reader = InputReader::new(reader, self.position());
+        let mut buf = vec![0; BUFFER_SIZE];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => self.push(&buf[..n])?,
+                Err(e) => {
+                    if self.is_started {
+                        self.finish()?;
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        self.finish()?;
+        Ok(self.summary.clone())
+    }
+
+    /// Like [`Dumper::print_all`], but pushes the buffer of `reader` without copying it first.
+    #[cfg(feature = "mmap")]
+    pub(crate) fn print_buffered<Reader: std::io::BufRead>(
+        &mut self,
+        mut reader: Reader,
+    ) -> io::Result<DumpSummary> {
+        loop {
+            match reader.fill_buf() {
+                Ok([]) => break,
+                Ok(buf) => {
+                    let len = buf.len();
+                    self.push(buf)?;
+                    reader.consume(len);
+                }
+                Err(e) => {
+                    if self.is_started {
+                        self.finish()?;
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        self.finish()?;
+        Ok(self.summary.clone())
+    }
+
+    /// Begins a dump with the first pushed byte.
+    fn begin_dump(&mut self) -> io::Result<()> {
+        self.is_started = true;
+        self.summary = DumpSummary::new(self.idx + self.display_offset);
+        self.renderer.begin_dump()
+    }
+
+    /// Renders the rows that are completed by `data`. The rest is kept until the next call, or
+    /// until [`Dumper::finish`].
+    pub fn push(&mut self, mut data: &[u8]) -> io::Result<()> {
+        if !self.is_started && !data.is_empty() {
+            self.begin_dump()?;
+        }
+        self.summary.add_bytes(data);
+        let row_len = self.line_buf.len();
+        while !data.is_empty() {
+            if self.line_len == 0 && data.len() >= row_len {
+                // complete rows are rendered from `data` directly, without copying them
+                let (row, rest) = data.split_at(row_len);
+                self.push_row(row)?;
+                data = rest;
+                if data.len() < row_len {
+                    // the last complete row stays the current one, like a copied row
+                    self.line_buf.copy_from_slice(row);
+                }
+                continue;
+            }
+            let n = (row_len - self.line_len).min(data.len());
+            self.line_buf[self.line_len..self.line_len + n].copy_from_slice(&data[..n]);
+            self.line_len += n;
+            data = &data[n..];
+            if self.line_len == self.line_buf.len() {
+                self.line_len = 0;
+                self.push_line()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders `len` bytes that could not be read, e.g. a bad block of a disk. They are shown as
+    /// unreadable cells, and squeezed like repeated bytes.
+    pub fn push_unreadable(&mut self, mut len: u64) -> io::Result<()> {
+        if !self.is_started && len > 0 {
+            self.begin_dump()?;
+        }
+        self.summary.add_unreadable(len);
+        while len > 0 {
+            let n = ((self.line_buf.len() - self.line_len) as u64).min(len) as usize;
+            self.line_buf[self.line_len..self.line_len + n].fill(0);
+            self.unreadable[self.line_len..self.line_len + n].fill(true);
+            self.unreadable_len += n;
+            self.line_len += n;
+            len -= n as u64;
+            if self.line_len == self.line_buf.len() {
+                self.line_len = 0;
+                self.push_line()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `row` consists of `squeeze_byte` only, or is unreadable if the squeezed rows are.
+    fn is_squeezed_row(&self, row: &[u8]) -> bool {
+        if self.squeeze_unreadable {
+            return self.unreadable_len == row.len();
+        }
+        self.unreadable_len == 0
+            && row
+                .chunks_exact(core::mem::size_of::<usize>())
+                .all(|w| usize::from_ne_bytes(w.try_into().unwrap()) == self.squeeze_byte)
+    }
+
+    /// Renders the complete line in `line_buf`, unless it is squeezed.
+    fn push_line(&mut self) -> io::Result<()> {
+        let line = core::mem::take(&mut self.line_buf);
+        let result = self.push_row(&line);
+        self.line_buf = line;
+        result
+    }
+
+    /// Renders a complete row, unless it is squeezed.
+    fn push_row(&mut self, row: &[u8]) -> io::Result<()> {
+        // squeeze is active, check if the line is the same
+        // skip print if still squeezed, otherwise print and deactivate squeeze
+        if matches!(self.squeezer, Squeezer::Print | Squeezer::Delete) {
+            if self.is_squeezed_row(row) {
+                if self.squeezer == Squeezer::Delete {
+                    self.summary.add_squeezed_row();
+                    self.idx += 8 * self.panels;
+                    self.clear_unreadable();
+                    return Ok(());
+                }
+            } else {
+                self.squeezer = Squeezer::Ignore;
+            }
+        }
+
+        // print the line
+        self.render_row(row)?;
+
+        // increment index to next line
+        self.idx += 8 * self.panels;
+
+        // change from print to delete if squeeze is still active
+        if self.squeezer == Squeezer::Print {
+            self.squeezer = Squeezer::Delete;
+        }
+
+        // repeat the first byte in the line until it's a usize
+        // compare that usize with each usize chunk in the line
+        // if they are all the same, change squeezer to print
+        // a line that could not be read at all is squeezed the same way
+        let repeat_byte = (row[0] as usize) * (usize::MAX / 255);
+        if !matches!(self.squeezer, Squeezer::Disabled | Squeezer::Delete) {
+            if self.unreadable_len == row.len() {
+                self.squeezer = Squeezer::Print;
+                self.squeeze_unreadable = true;
+            } else if self.unreadable_len == 0
+                && row
+                    .chunks_exact(core::mem::size_of::<usize>())
+                    .all(|w| usize::from_ne_bytes(w.try_into().unwrap()) == repeat_byte)
+            {
+                self.squeezer = Squeezer::Print;
+                self.squeeze_byte = repeat_byte;
+                self.squeeze_unreadable = false;
+            }
+        }
+        self.clear_unreadable();
+        Ok(())
+    }
+
+    fn clear_unreadable(&mut self) {
+        if self.unreadable_len > 0 {
+            self.unreadable.fill(false);
+            self.unreadable_len = 0;
+        }
+    }
+
+    /// Renders the incomplete last line and ends the dump. Afterwards, the next pushed byte
+    /// starts a new dump that continues at the current position.
+    pub fn finish(&mut self) -> io::Result<()> {
+        if !self.is_started {
+            self.summary = DumpSummary::new(self.idx + self.display_offset);
+            self.renderer.empty_dump()?;
+        } else if self.line_len > 0 || self.squeezer == Squeezer::Delete {
+            // the last line is incomplete, or empty if the ending is squeezed
+            // an incomplete line is never squeezed
+            if self.squeezer == Squeezer::Print {
+                self.squeezer = Squeezer::Ignore;
+            }
+            let line = core::mem::take(&mut self.line_buf);
+            let result = self.render_row(&line[..self.line_len]);
+            self.line_buf = line;
+            result?;
+            self.idx += self.line_len as u64;
+            self.clear_unreadable();
+        }
+        self.line_len = 0;
+        self.is_started = false;
+        self.renderer.end_dump()
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<R: Renderer> Dumper<R> {
+    /// Like [`Dumper::print_all`], but renders the rows on a pool of `threads` threads, or one
+    /// per CPU if it is 0. The output is the same, in the same order.
+    ///
+    /// The input is read in batches of a few chunks per thread, whose rows are rendered by forks
+    /// of the renderer. A chunk starts with the squeeze state of the two rows before it, which
+    /// is all that the state depends on.
+    pub(crate) fn print_all_parallel<Reader: Read>(
+        &mut self,
+        reader: Reader,
+        threads: usize,
+    ) -> io::Result<DumpSummary>
+    where
+        R: ForkRenderer,
+    {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .map_err(io::Error::other)?;
+        if pool.current_num_threads() == 1 {
+            return self.print_all(reader);
+        }
+        let mut reader = InputReader::new(reader, self.position());
+        let batch_size =
+            pool.current_num_threads() * PARALLEL_CHUNKS_PER_THREAD * PARALLEL_CHUNK_SIZE;
+        let mut buf = vec![0; batch_size];
+        loop {
+            let mut len = 0;
+            let mut error = None;
+            while len < buf.len() {
+                match reader.read(&mut buf[len..]) {
+                    Ok(0) => break,
+                    Ok(n) => len += n,
+                    Err(e) => {
+                        error = Some(e);
+                        break;
+                    }
+                }
+            }
+            self.push_parallel(&buf[..len], &pool)?;
+            if let Some(e) = error {
+                if self.is_started {
+                    self.finish()?;
+                }
+                return Err(e);
+            }
+            if len < buf.len() {
+                break;
+            }
+        }
+        self.finish()?;
+        Ok(self.summary.clone())
+    }
+
+    /// Like [`Dumper::push`], but renders the complete rows of `data` in chunks on the threads
+    /// of `pool`, and writes their output in order.
+    fn push_parallel(&mut self, data: &[u8], pool: &rayon::ThreadPool) -> io::Result<()>
+    where
+        R: ForkRenderer,
+    {
+        use rayon::prelude::*;
+
+        let row_len = self.line_buf.len();
+        let chunk_len = (PARALLEL_CHUNK_SIZE / row_len).max(2) * row_len;
+        // complete the current row first, so that the chunks are made of complete rows
+        let head = ((row_len - self.line_len) % row_len).min(data.len());
+        self.push(&data[..head])?;
+        let data = &data[head..];
+        if data.len() < 2 * chunk_len {
+            return self.push(data);
+        }
+        let (body, tail) = data.split_at(data.len() - data.len() % row_len);
+        if !self.is_started {
+            self.begin_dump()?;
+        }
+
+        let jobs: Vec<_> = (0..body.len())
+            .step_by(chunk_len)
+            .map(|start| {
+                let chunk = &body[start..(start + chunk_len).min(body.len())];
+                let mut dumper = Dumper::new(self.renderer.fork(), self.panels);
+                dumper.is_started = true;
+                dumper.display_offset = self.display_offset;
+                if start == 0 {
+                    // the first chunk continues the current state
+                    dumper.idx = self.idx;
+                    dumper.squeezer = self.squeezer;
+                    dumper.squeeze_byte = self.squeeze_byte;
+                    dumper.squeeze_unreadable = self.squeeze_unreadable;
+                    (dumper, &body[..0], chunk)
+                } else {
+                    // the others get it by rendering the two rows before them again
+                    let prime_start = start - 2 * row_len;
+                    dumper.idx = self.idx + prime_start as u64;
+                    if self.squeezer == Squeezer::Disabled {
+                        dumper.squeezer = Squeezer::Disabled;
+                    }
+                    (dumper, &body[prime_start..start], chunk)
+                }
+            })
+            .collect();
+        let rendered = pool.install(|| {
+            jobs.into_par_iter()
+                .map(|(mut dumper, prime, chunk)| {
+                    if !prime.is_empty() {
+                        dumper.push(prime)?;
+                        R::take_output(&mut dumper.renderer);
+                    }
+                    dumper.summary = DumpSummary::new(dumper.idx + dumper.display_offset);
+                    dumper.push(chunk)?;
+                    let output = R::take_output(&mut dumper.renderer);
+                    Ok((dumper, output))
+                })
+                .collect::<io::Result<Vec<_>>>()
+        })?;
+
+        let mut last = None;
+        for (dumper, output) in rendered {
+            self.renderer.write_output(&output)?;
+            self.summary.add(&dumper.summary);
+            last = Some(dumper);
+        }
+        let last = last.expect("the body has at least two chunks");
+        self.idx = last.idx;
+        self.squeezer = last.squeezer;
+        self.squeeze_byte = last.squeeze_byte;
+        self.squeeze_unreadable = last.squeeze_unreadable;
+        self.line_buf.copy_from_slice(&body[body.len() - row_len..]);
+        self.renderer.resume(&last.renderer);
+        self.push(tail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ByteCategory;
+
+    /// Records the calls it receives.
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl Renderer for Recorder {
+        fn begin_dump(&mut self) -> io::Result<()> {
+            self.0.push("begin".to_owned());
+            Ok(())
+        }
+
+        fn empty_dump(&mut self) -> io::Result<()> {
+            self.0.push("empty".to_owned());
+            Ok(())
+        }
+
+        fn end_dump(&mut self) -> io::Result<()> {
+            self.0.push("end".to_owned());
+            Ok(())
+        }
+
+        fn begin_row(&mut self, offset: u64) -> io::Result<()> {
+            self.0.push(format!("row {offset:#x}"));
+            Ok(())
+        }
+
+        fn byte(&mut self, index: usize, byte: u8) -> io::Result<()> {
+            self.0.push(format!("byte {index} {byte:#x}"));
+            Ok(())
+        }
+
+        fn partial_row(&mut self, len: usize) -> io::Result<()> {
+            self.0.push(format!("partial {len}"));
+            Ok(())
+        }
+
+        fn char_cell(&mut self, index: usize, byte: u8) -> io::Result<()> {
+            self.0.push(format!("char {index} {byte:#x}"));
+            Ok(())
+        }
+
+        fn unreadable_byte(&mut self, index: usize) -> io::Result<()> {
+            self.0.push(format!("unreadable byte {index}"));
+            Ok(())
+        }
+
+        fn unreadable_char(&mut self, index: usize) -> io::Result<()> {
+            self.0.push(format!("unreadable char {index}"));
+            Ok(())
+        }
+
+        fn end_row(&mut self) -> io::Result<()> {
+            self.0.push("end row".to_owned());
+            Ok(())
+        }
+
+        fn squeeze_marker(&mut self, offset: u64) -> io::Result<()> {
+            self.0.push(format!("squeeze {offset:#x}"));
+            Ok(())
+        }
+    }
+
+    fn record(input: &[u8], squeeze: bool) -> Vec<String> {
+        let mut dumper = Dumper::new(Recorder::default(), 1).enable_squeezing(squeeze);
+        dumper.display_offset(0x100);
+        dumper.print_all(input).unwrap();
+        dumper.into_renderer().0
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(record(b"", true), ["empty", "end"]);
+    }
+
+    #[test]
+    fn rows() {
+        let calls = record(b"0123456789", true);
+        let mut expected = vec!["begin".to_owned(), "row 0x100".to_owned()];
+        expected.extend((0..8).map(|i| format!("byte {i} {:#x}", b'0' + i)));
+        expected.extend((0..8).map(|i| format!("char {i} {:#x}", b'0' + i)));
+        expected.push("end row".to_owned());
+        expected.push("row 0x108".to_owned());
+        expected.extend(["byte 0 0x38", "byte 1 0x39", "partial 2"].map(String::from));
+        expected.extend(["char 0 0x38", "char 1 0x39", "end row", "end"].map(String::from));
+        assert_eq!(calls, expected);
+    }
+
+    #[test]
+    fn squeeze() {
+        let calls = record(&[0; 40], true);
+        let rows: Vec<_> = calls
+            .iter()
+            .filter(|c| c.starts_with("row") || c.starts_with("squeeze") || *c == "partial 0")
+            .collect();
+        assert_eq!(
+            rows,
+            ["row 0x100", "squeeze 0x108", "row 0x128", "partial 0"]
+        );
+
+        let calls = record(&[0; 40], false);
+        assert_eq!(calls.iter().filter(|c| c.starts_with("row")).count(), 5);
+        assert!(!calls.iter().any(|c| c.starts_with("squeeze")));
+    }
+
+    /// Returns `data` in chunks of 4 bytes, with an interrupted read before every chunk, and
+    /// fails at the end.
+    struct FlakyReader<'a> {
+        data: &'a [u8],
+        interrupted: bool,
+    }
+
+    impl Read for FlakyReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.interrupted = !self.interrupted;
+            if self.interrupted {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            if self.data.is_empty() {
+                return Err(io::Error::other("broken"));
+            }
+            let n = buf.len().min(self.data.len()).min(4);
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn read_error() {
+        let mut dumper = Dumper::new(Recorder::default(), 1);
+        dumper.display_offset(0x100);
+        let reader = FlakyReader {
+            data: b"0123456789",
+            interrupted: false,
+        };
+        let err = dumper.print_all(reader).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to read the input at offset 0x10a: broken"
+        );
+        let read_error = err.get_ref().unwrap().downcast_ref::<io::ReadError>();
+        assert_eq!(read_error.unwrap().offset(), 0x10a);
+        // the dump still ends with the bytes that were read
+        assert_eq!(dumper.into_renderer().0, record(b"0123456789", true));
+    }
+
+    #[test]
+    fn unreadable() {
+        let mut dumper = Dumper::new(Recorder::default(), 1);
+        dumper.push(b"0123456").unwrap();
+        dumper.push_unreadable(34).unwrap();
+        dumper.push(b"9").unwrap();
+        dumper.finish().unwrap();
+        let calls = dumper.into_renderer().0;
+        let rows: Vec<_> = calls
+            .iter()
+            .filter(|c| c.starts_with("row") || c.starts_with("squeeze"))
+            .collect();
+        assert_eq!(rows, ["row 0x0", "row 0x8", "squeeze 0x10", "row 0x28"]);
+        let first_row: Vec<_> = calls.iter().skip(1).take(18).collect();
+        assert_eq!(first_row[7], "byte 6 0x36");
+        assert_eq!(first_row[8], "unreadable byte 7");
+        assert_eq!(first_row[16], "unreadable char 7");
+        assert!(calls.contains(&"byte 1 0x39".to_owned()));
+        assert!(calls.contains(&"unreadable char 0".to_owned()));
+    }
+
+    #[test]
+    fn summary() {
+        let mut dumper = Dumper::new(Recorder::default(), 1);
+        dumper.display_offset(0x100);
+        let mut input = vec![0; 40];
+        input.extend_from_slice(b"abc\n");
+        let summary = dumper.print_all(&input[..]).unwrap();
+        assert_eq!(summary.bytes(), 44);
+        assert_eq!(summary.rows_printed(), 2);
+        assert_eq!(summary.rows_squeezed(), 4);
+        assert_eq!(summary.end_offset(), 0x100 + 44);
+        assert_eq!(summary.category_count(ByteCategory::Null), 40);
+        assert_eq!(summary.category_count(ByteCategory::AsciiPrintable), 3);
+        assert_eq!(summary.category_count(ByteCategory::AsciiWhitespace), 1);
+        assert_eq!(summary.category_count(ByteCategory::NonAscii), 0);
+
+        // the next dump continues at the end of the previous one
+        let summary = dumper.print_all(&b"d"[..]).unwrap();
+        assert_eq!(summary.bytes(), 1);
+        assert_eq!(summary.rows_printed(), 1);
+        assert_eq!(summary.end_offset(), 0x100 + 45);
+    }
+}
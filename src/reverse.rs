@@ -0,0 +1,536 @@
+use std::borrow::Cow;
+use std::io::{self, BufRead, Read, Write};
+
+use crate::{Base, Endianness};
+
+/// Converts a hex dump back into the binary data it was created from.
+///
+/// Understands hexyl's own output (any border style, base, group size and endianness, colored or
+/// not), as well as the output of `xxd` and `hexdump -C`. Rows that were squeezed into a single
+/// `*` line are expanded again, which requires the dump to contain a position panel.
+pub struct Reverser {
+    base: Base,
+    endianness: Endianness,
+}
+
+impl Default for Reverser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reverser {
+    pub fn new() -> Self {
+        Reverser {
+            base: Base::Hexadecimal,
+            endianness: Endianness::Big,
+        }
+    }
+
+    /// The base that was used to write the bytes of a hexyl dump. `xxd` and `hexdump` dumps are
+    /// always read as hexadecimal.
+    pub fn with_base(mut self, base: Base) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// The byte order of the groups in the dump.
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
+    /// Parse the dump from `reader` and write the bytes to `writer`. Returns the number of bytes
+    /// written.
+    pub fn reverse<Reader: BufRead, Writer: Write>(
+        &self,
+        reader: Reader,
+        mut writer: Writer,
+    ) -> io::Result<u64> {
+        let mut state = ReverseState::default();
+        let mut panels = None;
+
+        for (line_idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line_no = line_idx + 1;
+            let parsed = self
+                .parse_line(&line, &mut panels)
+                .map_err(|msg| invalid_data(line_no, msg))?;
+            match parsed {
+                Line::Skip => {}
+                Line::Squeeze => state.squeezed = true,
+                Line::Data { offset, bytes } => state
+                    .write_row(&mut writer, offset, &bytes)
+                    .map_err(|e| match e {
+                        RowError::Io(e) => e,
+                        RowError::Invalid(msg) => invalid_data(line_no, msg),
+                    })?,
+            }
+        }
+
+        if state.squeezed {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "dump ends with a squeezed line, but there is no position to expand it to",
+            ));
+        }
+
+        writer.flush()?;
+        Ok(state.written)
+    }
+
+    fn parse_line(&self, line: &str, panels: &mut Option<usize>) -> Result<Line, String> {
+        let line = strip_escape_sequences(line.trim_end_matches('\r'));
+        match line.chars().next() {
+            None => Ok(Line::Skip),
+            Some('*') => Ok(Line::Squeeze),
+            Some(c) if c.is_ascii_hexdigit() => parse_foreign_line(&line, self.endianness),
+            Some(_) => self.parse_hexyl_line(&line, panels),
+        }
+    }
+
+    fn parse_hexyl_line(&self, line: &str, panels: &mut Option<usize>) -> Result<Line, String> {
+        // top and bottom borders: every column that is wider than the position or a character
+        // panel is a hex panel
+        if line.starts_with(['┌', '└', '+']) {
+            let num_panels = line
+                .split(['┌', '┬', '┐', '└', '┴', '┘', '+'])
+                .filter(|column| column.chars().count() > 8)
+                .count();
+            panels.get_or_insert(num_panels);
+            return Ok(Line::Skip);
+        }
+        if line.trim().is_empty() {
+            return Ok(Line::Skip);
+        }
+
+        // With the border characters replaced by spaces, all border styles share the layout of
+        // `--border=none`: groups are separated by one space, panels by three spaces and the
+        // character panel by two spaces (or by the padding of an incomplete line).
+        let line: String = line
+            .chars()
+            .map(|c| {
+                if matches!(c, '│' | '┊' | '|') {
+                    ' '
+                } else {
+                    c
+                }
+            })
+            .collect();
+        let rest = line.strip_prefix(' ').unwrap_or(&line);
+
+        if rest.starts_with('*') {
+            return Ok(Line::Squeeze);
+        }
+        if rest.trim_start().starts_with("No content") {
+            return Ok(Line::Skip);
+        }
+
+        let (offset, rest) = if rest.starts_with(' ') {
+            (None, rest)
+        } else {
+            let end = rest.find(' ').unwrap_or(rest.len());
+            let offset = u64::from_str_radix(&rest[..end], 16)
+                .map_err(|_| format!("invalid position {:?}", &rest[..end]))?;
+            (Some(offset), &rest[end..])
+        };
+
+        let mut bytes = vec![];
+        let mut area = rest.trim_start_matches(' ');
+        let mut group_len = 0;
+        loop {
+            let (group, tail) = area.split_once(' ').unwrap_or((area, ""));
+            if group.is_empty() {
+                break;
+            }
+            if !bytes.is_empty() && bytes.len() % 8 == 0 {
+                // A character panel that starts with a space looks just like another hex panel.
+                // Unless the number of panels is known, only accept it if it could be one.
+                match *panels {
+                    Some(panels) if bytes.len() == 8 * panels => break,
+                    Some(_) => {}
+                    None => {
+                        if group.len() != group_len
+                            || self.decode_group(group, &mut vec![]).is_err()
+                        {
+                            break;
+                        }
+                    }
+                }
+            }
+            self.decode_group(group, &mut bytes)?;
+            group_len = group.len();
+
+            // `split_once` has already consumed the first space of the gap
+            let gap = 1 + tail.len() - tail.trim_start_matches(' ').len();
+            let end_of_panel = bytes.len() % 8 == 0;
+            match (gap, end_of_panel) {
+                (1, false) | (3, true) if gap <= tail.len() => area = &tail[gap - 1..],
+                (2, true) => {
+                    // the character panel directly follows a complete line
+                    panels.get_or_insert(bytes.len() / 8);
+                    break;
+                }
+                _ => break,
+            }
+        }
+
+        Ok(Line::Data { offset, bytes })
+    }
+
+    fn decode_group(&self, token: &str, bytes: &mut Vec<u8>) -> Result<(), String> {
+        let (radix, digits) = match self.base {
+            Base::Binary => (2, 8),
+            Base::Octal => (8, 3),
+            Base::Decimal => (10, 3),
+            Base::Hexadecimal => (16, 2),
+        };
+        decode_group(token, radix, digits, self.endianness, bytes)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Line {
+    /// A line without any data, e.g. a border.
+    Skip,
+    /// A line that stands in for one or more rows that are identical to the previous one.
+    Squeeze,
+    Data {
+        offset: Option<u64>,
+        bytes: Vec<u8>,
+    },
+}
+
+/// Removes the escape sequences of a colored dump, like `\x1b[38;5;2m`, which all end with a byte
+/// in the range `@` to `~`.
+fn strip_escape_sequences(line: &str) -> Cow<'_, str> {
+    if !line.contains('\x1b') {
+        return Cow::Borrowed(line);
+    }
+    let mut stripped = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+        } else if chars.clone().next() == Some('[') {
+            chars.find(|c| ('@'..='~').contains(c) && *c != '[');
+        }
+    }
+    Cow::Owned(stripped)
+}
+
+/// Parses a line of `xxd` or `hexdump -C` output. Both start with the offset, which is followed by
+/// a colon in the case of `xxd`.
+fn parse_foreign_line(line: &str, endianness: Endianness) -> Result<Line, String> {
+    let end = line
+        .find(|c: char| !c.is_ascii_hexdigit())
+        .unwrap_or(line.len());
+    let offset = u64::from_str_radix(&line[..end], 16)
+        .map_err(|_| format!("invalid position {:?}", &line[..end]))?;
+    let rest = &line[end..];
+
+    let mut bytes = vec![];
+    if let Some(rest) = rest.strip_prefix(':') {
+        // xxd: the hex area ends at the first gap of two or more spaces
+        for token in xxd_groups(rest.trim_start_matches(' ')) {
+            decode_group(token, 16, 2, endianness, &mut bytes)?;
+        }
+    } else {
+        // hexdump -C: everything up to the `|`-delimited character column
+        let hex_area = rest.split('|').next().unwrap_or_default();
+        for token in hex_area.split_whitespace() {
+            decode_group(token, 16, 2, Endianness::Big, &mut bytes)?;
+        }
+    }
+
+    Ok(Line::Data {
+        offset: Some(offset),
+        bytes,
+    })
+}
+
+/// Splits the hex area of an `xxd` line into groups. The area ends at the first run of two or more
+/// spaces.
+fn xxd_groups(area: &str) -> impl Iterator<Item = &str> {
+    let mut rest = Some(area);
+    std::iter::from_fn(move || {
+        let area = rest.take()?;
+        let end = area.find(' ').unwrap_or(area.len());
+        if end == 0 {
+            return None;
+        }
+        let (token, tail) = area.split_at(end);
+        let gap = tail.len() - tail.trim_start_matches(' ').len();
+        if gap == 1 && gap < tail.len() {
+            rest = Some(&tail[gap..]);
+        }
+        Some(token)
+    })
+}
+
+fn decode_group(
+    token: &str,
+    radix: u32,
+    digits: usize,
+    endianness: Endianness,
+    bytes: &mut Vec<u8>,
+) -> Result<(), String> {
+    if !token.is_ascii() || token.len() % digits != 0 {
+        return Err(format!("invalid group {token:?}"));
+    }
+    let start = bytes.len();
+    for chunk in token.as_bytes().chunks(digits) {
+        // the chunk is ASCII, so this can't fail
+        let chunk = std::str::from_utf8(chunk).unwrap();
+        let byte =
+            u8::from_str_radix(chunk, radix).map_err(|_| format!("invalid group {token:?}"))?;
+        bytes.push(byte);
+    }
+    if let Endianness::Little = endianness {
+        bytes[start..].reverse();
+    }
+    Ok(())
+}
+
+fn invalid_data(line_no: usize, msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("line {line_no}: {msg}"))
+}
+
+enum RowError {
+    Io(io::Error),
+    Invalid(String),
+}
+
+impl From<io::Error> for RowError {
+    fn from(e: io::Error) -> Self {
+        RowError::Io(e)
+    }
+}
+
+#[derive(Default)]
+struct ReverseState {
+    /// The position of the first row, which all other positions are relative to.
+    start: Option<u64>,
+    written: u64,
+    last_row: Vec<u8>,
+    squeezed: bool,
+}
+
+impl ReverseState {
+    fn write_row<Writer: Write>(
+        &mut self,
+        writer: &mut Writer,
+        offset: Option<u64>,
+        bytes: &[u8],
+    ) -> Result<(), RowError> {
+        if let Some(offset) = offset {
+            let start = *self.start.get_or_insert(offset);
+            let target = offset.checked_sub(start).ok_or_else(|| {
+                RowError::Invalid(format!(
+                    "position {offset:#x} is before the start of the dump"
+                ))
+            })?;
+            if target < self.written {
+                return Err(RowError::Invalid(format!(
+                    "position {offset:#x} overlaps the previous line"
+                )));
+            }
+            let mut missing = target - self.written;
+            if self.squeezed && !self.last_row.is_empty() {
+                // expand the squeezed rows by repeating the last printed one
+                while missing > 0 {
+                    let n = missing.min(self.last_row.len() as u64) as usize;
+                    writer.write_all(&self.last_row[..n])?;
+                    missing -= n as u64;
+                }
+            } else {
+                io::copy(&mut io::repeat(0).take(missing), writer)?;
+            }
+            self.written = target;
+        } else if self.squeezed {
+            return Err(RowError::Invalid(
+                "can not expand a squeezed line without a position panel".to_owned(),
+            ));
+        }
+
+        self.squeezed = false;
+        writer.write_all(bytes)?;
+        self.written += bytes.len() as u64;
+        if !bytes.is_empty() {
+            self.last_row = bytes.to_vec();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BorderStyle, CharacterTable, PrinterBuilder};
+
+    fn reverse(dump: &str, reverser: Reverser) -> Vec<u8> {
+        let mut output = vec![];
+        reverser.reverse(dump.as_bytes(), &mut output).unwrap();
+        output
+    }
+
+    fn test_input() -> Vec<u8> {
+        let mut input = b"hexyl \x00\x01\x02\xfe\xff \t\r\n reverse".to_vec();
+        input.extend_from_slice(&[0; 100]);
+        input.extend_from_slice(b"after the squeeze");
+        input
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = test_input();
+        for border in [BorderStyle::Unicode, BorderStyle::Ascii, BorderStyle::None] {
+            for (base, group_size) in [
+                (Base::Hexadecimal, 1),
+                (Base::Hexadecimal, 2),
+                (Base::Hexadecimal, 4),
+                (Base::Hexadecimal, 8),
+                (Base::Binary, 1),
+                (Base::Octal, 2),
+                (Base::Decimal, 4),
+            ] {
+                for endianness in [Endianness::Big, Endianness::Little] {
+                    for (panels, show_char_panel) in [(1, true), (2, true), (3, false)] {
+                        let mut dump = vec![];
+                        PrinterBuilder::new(&mut dump)
+                            .show_color(false)
+                            .show_char_panel(show_char_panel)
+                            .with_border_style(border)
+                            .num_panels(panels)
+                            .group_size(group_size)
+                            .with_base(base)
+                            .endianness(endianness)
+                            .character_table(CharacterTable::Default)
+                            .build()
+                            .print_all(&input[..])
+                            .unwrap();
+                        let dump = String::from_utf8(dump).unwrap();
+
+                        let output = reverse(
+                            &dump,
+                            Reverser::new().with_base(base).endianness(endianness),
+                        );
+                        assert_eq!(output, input, "failed to reverse\n{dump}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip_with_colors() {
+        let input = test_input();
+        for base in [Base::Hexadecimal, Base::Binary] {
+            let mut dump = vec![];
+            PrinterBuilder::new(&mut dump)
+                .show_color(true)
+                .with_base(base)
+                .build()
+                .print_all(&input[..])
+                .unwrap();
+            let dump = String::from_utf8(dump).unwrap();
+            assert!(dump.contains('\x1b'));
+            assert_eq!(
+                reverse(&dump, Reverser::new().with_base(base)),
+                input,
+                "failed to reverse\n{dump}"
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip_without_position() {
+        let input = b"no position panel, but no squeezing either";
+        let mut dump = vec![];
+        PrinterBuilder::new(&mut dump)
+            .show_color(false)
+            .show_position_panel(false)
+            .with_border_style(BorderStyle::None)
+            .build()
+            .print_all(&input[..])
+            .unwrap();
+        let dump = String::from_utf8(dump).unwrap();
+        assert_eq!(reverse(&dump, Reverser::new()), input);
+    }
+
+    #[test]
+    fn squeeze_without_position_fails() {
+        let mut dump = vec![];
+        PrinterBuilder::new(&mut dump)
+            .show_color(false)
+            .show_position_panel(false)
+            .build()
+            .print_all(io::repeat(0).take(64))
+            .unwrap();
+        let dump = String::from_utf8(dump).unwrap();
+        assert!(Reverser::new()
+            .reverse(dump.as_bytes(), io::sink())
+            .is_err());
+    }
+
+    #[test]
+    fn empty_dump() {
+        let mut dump = vec![];
+        PrinterBuilder::new(&mut dump)
+            .show_color(false)
+            .build()
+            .print_all(io::empty())
+            .unwrap();
+        let dump = String::from_utf8(dump).unwrap();
+        assert_eq!(reverse(&dump, Reverser::new()), b"");
+    }
+
+    #[test]
+    fn xxd() {
+        let dump = "\
+00000000: 7f45 4c46 0201 0100 0000 0000 0000 0000  .ELF............
+00000010: 0200 3e00 0100 0000 0010 4000 0000 0000  ..>.......@.....
+00000020: 4000 0000 0000 0000                      @.......
+";
+        assert_eq!(
+            reverse(dump, Reverser::new()),
+            b"\x7fELF\x02\x01\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+              \x02\x00\x3e\x00\x01\x00\x00\x00\x00\x10\x40\x00\x00\x00\x00\x00\
+              \x40\x00\x00\x00\x00\x00\x00\x00"
+        );
+    }
+
+    #[test]
+    fn xxd_little_endian() {
+        let dump = "00000000: 464c457f 00010102  .ELF....\n";
+        assert_eq!(
+            reverse(dump, Reverser::new().endianness(Endianness::Little)),
+            b"\x7fELF\x02\x01\x01\x00"
+        );
+    }
+
+    #[test]
+    fn hexdump() {
+        let dump = "\
+00000000  7f 45 4c 46 02 01 01 00  00 00 00 00 00 00 00 00  |.ELF............|
+00000010  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|
+*
+00000040  01 02 03                                          |...|
+00000043
+";
+        let mut expected = b"\x7fELF\x02\x01\x01\x00".to_vec();
+        expected.extend_from_slice(&[0; 0x38]);
+        expected.extend_from_slice(b"\x01\x02\x03");
+        assert_eq!(reverse(dump, Reverser::new()), expected);
+    }
+
+    #[test]
+    fn invalid_group() {
+        let dump = "│00000000│ 30 3x 32 ┊ │012 ┊ │\n";
+        let err = Reverser::new()
+            .reverse(dump.as_bytes(), io::sink())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "line 1: invalid group \"3x\"");
+    }
+}
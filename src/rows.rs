@@ -0,0 +1,316 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::io::{self, InputReader, Read};
+use crate::render::{Dumper, Renderer};
+use crate::{ConfigError, DumpSummary, MAX_PANELS};
+
+/// The number of bytes that [`Rows`] reads at once.
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// A row of a dump, see [`Rows`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Row {
+    /// The position of the first byte, including the display offset.
+    pub offset: u64,
+    /// The bytes of the row. A squeezed row has the bytes of the row before, which it repeats.
+    pub bytes: Vec<u8>,
+    /// Whether the row stands for squeezed rows, which are shown as `*`.
+    pub squeezed: Option<SqueezeInfo>,
+    /// Whether this is the incomplete last row.
+    pub is_partial: bool,
+}
+
+/// The rows that a squeezed [`Row`] stands for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SqueezeInfo {
+    /// The number of rows, which all repeat the row before the squeezed one.
+    pub rows: u64,
+}
+
+/// Iterates over the rows of a dump of `Reader`, with the same rows as [`Printer::print_all`]
+/// prints. This lets a custom view render the rows itself, e.g. lazily.
+///
+/// ```
+/// use hexyl::Rows;
+///
+/// let input = [0; 64];
+/// let rows: Vec<_> = Rows::new(&input[..], 2).collect::<Result<_, _>>().unwrap();
+/// assert_eq!(rows.len(), 2);
+/// assert_eq!(rows[1].offset, 16);
+/// assert_eq!(rows[1].squeezed.unwrap().rows, 3);
+/// ```
+///
+/// [`Printer::print_all`]: crate::Printer::print_all
+pub struct Rows<Reader: Read> {
+    reader: InputReader<Reader>,
+    dumper: Dumper<RowCollector>,
+    buf: Vec<u8>,
+    /// The read error to return after the rows that were read before it.
+    error: Option<io::Error>,
+    is_done: bool,
+}
+
+impl<Reader: Read> Rows<Reader> {
+    /// The rows of `reader`, with `8 * panels` bytes each.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of panels is invalid, see [`Rows::try_new`].
+    pub fn new(reader: Reader, panels: u64) -> Self {
+        match Self::try_new(reader, panels) {
+            Ok(rows) => rows,
+            Err(e) => panic!("invalid rows configuration: {e}"),
+        }
+    }
+
+    /// The rows of `reader`, with `8 * panels` bytes each, or the error of a number of panels that
+    /// [`PrinterBuilder::try_build`](crate::PrinterBuilder::try_build) rejects, too.
+    pub fn try_new(reader: Reader, panels: u64) -> Result<Self, ConfigError> {
+        if panels == 0 {
+            return Err(ConfigError::NoPanels);
+        }
+        if panels > MAX_PANELS {
+            return Err(ConfigError::TooManyPanels(panels));
+        }
+        Ok(Rows {
+            reader: InputReader::new(reader, 0),
+            dumper: Dumper::new(RowCollector::new(8 * panels), panels),
+            buf: vec![0; BUFFER_SIZE],
+            error: None,
+            is_done: false,
+        })
+    }
+
+    /// Whether to squeeze repeated rows of identical bytes into a single row.
+    pub fn enable_squeezing(mut self, enable: bool) -> Self {
+        self.dumper = self.dumper.enable_squeezing(enable);
+        self
+    }
+
+    pub fn display_offset(mut self, display_offset: u64) -> Self {
+        self.dumper.display_offset(display_offset);
+        self.reader = InputReader::new(self.reader.into_inner(), display_offset);
+        self
+    }
+
+    /// The summary of the rows so far, or of all rows at the end.
+    pub fn summary(&self) -> &DumpSummary {
+        self.dumper.summary()
+    }
+}
+
+impl<Reader: Read> Iterator for Rows<Reader> {
+    type Item = io::Result<Row>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(row) = self.dumper.renderer_mut().rows.pop_front() {
+                return Some(Ok(row));
+            }
+            if let Some(e) = self.error.take() {
+                return Some(Err(e));
+            }
+            if self.is_done {
+                return None;
+            }
+            let result = match self.reader.read(&mut self.buf) {
+                Ok(0) => {
+                    self.is_done = true;
+                    self.dumper.finish()
+                }
+                Ok(n) => self.dumper.push(&self.buf[..n]),
+                Err(e) => {
+                    // end with the rows that were read, like `Dumper::print_all`
+                    self.is_done = true;
+                    self.error = Some(e);
+                    self.dumper.finish()
+                }
+            };
+            if let Err(e) = result {
+                self.is_done = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// Collects the rows that a [`Dumper`] renders.
+struct RowCollector {
+    row_len: u64,
+    rows: VecDeque<Row>,
+    /// The row that is being rendered.
+    current: Row,
+    /// The squeezed row, until the next row tells how many rows it stands for.
+    squeezed: Option<Row>,
+}
+
+impl RowCollector {
+    fn new(row_len: u64) -> Self {
+        RowCollector {
+            row_len,
+            rows: VecDeque::new(),
+            current: Row {
+                offset: 0,
+                bytes: vec![],
+                squeezed: None,
+                is_partial: false,
+            },
+            squeezed: None,
+        }
+    }
+}
+
+impl Renderer for RowCollector {
+    fn begin_dump(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn empty_dump(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn end_dump(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn begin_row(&mut self, offset: u64) -> io::Result<()> {
+        if let Some(mut row) = self.squeezed.take() {
+            row.squeezed = Some(SqueezeInfo {
+                rows: (offset - row.offset) / self.row_len,
+            });
+            self.rows.push_back(row);
+        }
+        self.current.offset = offset;
+        self.current.bytes.clear();
+        self.current.is_partial = false;
+        Ok(())
+    }
+
+    fn byte(&mut self, _index: usize, byte: u8) -> io::Result<()> {
+        self.current.bytes.push(byte);
+        Ok(())
+    }
+
+    fn partial_row(&mut self, _len: usize) -> io::Result<()> {
+        self.current.is_partial = true;
+        Ok(())
+    }
+
+    fn char_cell(&mut self, _index: usize, _byte: u8) -> io::Result<()> {
+        Ok(())
+    }
+
+    // `Rows` never pushes unreadable bytes
+    fn unreadable_byte(&mut self, _index: usize) -> io::Result<()> {
+        self.current.bytes.push(0);
+        Ok(())
+    }
+
+    fn unreadable_char(&mut self, _index: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn end_row(&mut self) -> io::Result<()> {
+        // the empty row that only shows where a squeezed ending ends is not a row of its own
+        if !self.current.bytes.is_empty() {
+            self.rows.push_back(self.current.clone());
+        }
+        Ok(())
+    }
+
+    fn squeeze_marker(&mut self, offset: u64) -> io::Result<()> {
+        self.squeezed = Some(Row {
+            offset,
+            bytes: self.current.bytes.clone(),
+            squeezed: Some(SqueezeInfo { rows: 0 }),
+            is_partial: false,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(input: &[u8], squeeze: bool) -> Vec<Row> {
+        Rows::new(input, 1)
+            .enable_squeezing(squeeze)
+            .display_offset(0x100)
+            .collect::<io::Result<_>>()
+            .unwrap()
+    }
+
+    fn row(offset: u64, bytes: &[u8], squeezed_rows: Option<u64>, is_partial: bool) -> Row {
+        Row {
+            offset,
+            bytes: bytes.to_vec(),
+            squeezed: squeezed_rows.map(|rows| SqueezeInfo { rows }),
+            is_partial,
+        }
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(rows(b"", true), []);
+    }
+
+    #[test]
+    fn partial_last_row() {
+        assert_eq!(
+            rows(b"0123456789", true),
+            [
+                row(0x100, b"01234567", None, false),
+                row(0x108, b"89", None, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn squeeze() {
+        let mut input = vec![0; 40];
+        input.extend_from_slice(b"abc");
+        assert_eq!(
+            rows(&input, true),
+            [
+                row(0x100, &[0; 8], None, false),
+                row(0x108, &[0; 8], Some(4), false),
+                row(0x128, b"abc", None, true),
+            ]
+        );
+        assert_eq!(rows(&input, false).len(), 6);
+
+        // a squeezed ending
+        assert_eq!(
+            rows(&[0; 40], true),
+            [
+                row(0x100, &[0; 8], None, false),
+                row(0x108, &[0; 8], Some(4), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_panels() {
+        let error = |panels| Rows::try_new(&b""[..], panels).err();
+        assert_eq!(error(0), Some(ConfigError::NoPanels));
+        assert_eq!(
+            error(MAX_PANELS + 1),
+            Some(ConfigError::TooManyPanels(MAX_PANELS + 1))
+        );
+        assert_eq!(error(MAX_PANELS), None);
+    }
+
+    #[test]
+    fn same_rows_as_dumper() {
+        let input: Vec<u8> = (0..100u8).chain([0xff; 50]).chain(0..10).collect();
+        let rows = Rows::new(&input[..], 2)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        let mut dumper = Dumper::new(RowCollector::new(16), 2);
+        dumper.print_all(&input[..]).unwrap();
+        assert_eq!(rows, Vec::from(dumper.into_renderer().rows));
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{Byte, ByteCategory};
+
+/// What a dump consisted of, see [`Printer::print_all`](crate::Printer::print_all) and
+/// [`Printer::summary`](crate::Printer::summary).
+///
+/// Rows are only counted by the formats that are printed row by row, which are the hexyl layout of
+/// a dump, JSON and HTML.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DumpSummary {
+    bytes: u64,
+    unreadable_bytes: u64,
+    rows_printed: u64,
+    rows_squeezed: u64,
+    end_offset: u64,
+    /// How often every byte value occurred.
+    byte_counts: [u64; 256],
+}
+
+impl DumpSummary {
+    pub(crate) fn new(start_offset: u64) -> Self {
+        DumpSummary {
+            bytes: 0,
+            unreadable_bytes: 0,
+            rows_printed: 0,
+            rows_squeezed: 0,
+            end_offset: start_offset,
+            byte_counts: [0; 256],
+        }
+    }
+
+    /// The number of bytes that were read from the input.
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// The number of bytes that could not be read, see
+    /// [`Printer::write_unreadable`](crate::Printer::write_unreadable).
+    pub fn unreadable_bytes(&self) -> u64 {
+        self.unreadable_bytes
+    }
+
+    /// The number of rows that were printed with their bytes.
+    pub fn rows_printed(&self) -> u64 {
+        self.rows_printed
+    }
+
+    /// The number of rows that were squeezed into a `*` row, because they repeat the row before.
+    pub fn rows_squeezed(&self) -> u64 {
+        self.rows_squeezed
+    }
+
+    /// The offset after the last byte, including the display offset.
+    pub fn end_offset(&self) -> u64 {
+        self.end_offset
+    }
+
+    /// The number of bytes of `category` that were read.
+    pub fn category_count(&self, category: ByteCategory) -> u64 {
+        (0..=u8::MAX)
+            .filter(|&b| Byte(b).category() == category)
+            .map(|b| self.byte_counts[b as usize])
+            .sum()
+    }
+
+    pub(crate) fn add_bytes(&mut self, data: &[u8]) {
+        for &b in data {
+            self.byte_counts[b as usize] += 1;
+        }
+        self.bytes += data.len() as u64;
+        self.end_offset += data.len() as u64;
+    }
+
+    pub(crate) fn add_unreadable(&mut self, len: u64) {
+        self.unreadable_bytes += len;
+        self.end_offset += len;
+    }
+
+    /// Adds the counts of `other`, which continues the dump.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn add(&mut self, other: &DumpSummary) {
+        self.bytes += other.bytes;
+        self.unreadable_bytes += other.unreadable_bytes;
+        self.rows_printed += other.rows_printed;
+        self.rows_squeezed += other.rows_squeezed;
+        self.end_offset = other.end_offset;
+        for (count, other) in self.byte_counts.iter_mut().zip(&other.byte_counts) {
+            *count += other;
+        }
+    }
+
+    pub(crate) fn add_printed_row(&mut self) {
+        self.rows_printed += 1;
+    }
+
+    pub(crate) fn add_squeezed_row(&mut self) {
+        self.rows_squeezed += 1;
+    }
+}
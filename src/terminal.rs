@@ -0,0 +1,812 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::io::{self, Write};
+
+use crate::render::Renderer;
+use crate::{
+    html, Base, BorderElements, BorderStyle, Byte, ByteClassifier, CharacterTable, ColorType,
+    DumpHeader, Endianness, OutputFormat,
+};
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// The text of a cell, precomputed for every byte value, optionally with the escape sequence of
+/// its color in front.
+#[derive(Clone, Copy)]
+struct Cell {
+    len: u8,
+    text: [u8; 32],
+}
+
+impl Cell {
+    fn new(parts: &[&[u8]]) -> Cell {
+        let mut cell = Cell {
+            len: 0,
+            text: [0; 32],
+        };
+        for part in parts {
+            let start = cell.len as usize;
+            cell.text[start..start + part.len()].copy_from_slice(part);
+            cell.len += part.len() as u8;
+        }
+        cell
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.text[..self.len as usize]
+    }
+
+    /// Appends the cell to `buf`. The whole array is copied and cut off afterwards, because a
+    /// copy of a fixed size is a lot faster than one of a few bytes of any size.
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        let len = buf.len() + self.len as usize;
+        buf.extend_from_slice(&self.text);
+        buf.truncate(len);
+    }
+}
+
+/// The default renderer: hexyl's own layout with ANSI colors, or with CSS classes in HTML output.
+pub(crate) struct TerminalRenderer<Writer: Write> {
+    pub(crate) writer: Writer,
+    /// The output of the current row, which is written at once at its end.
+    buf: Vec<u8>,
+    show_char_panel: bool,
+    show_position_panel: bool,
+    show_color: bool,
+    curr_color: Option<ColorType>,
+    /// The color of the open `<span>` in HTML output.
+    html_span: Option<ColorType>,
+    border_style: BorderStyle,
+    outer_sep: String,
+    inner_sep: String,
+    /// The cells of the hex panel, by byte value.
+    hex_cells: Vec<Cell>,
+    /// The cells of the character panel, by byte value.
+    char_cells: Vec<Cell>,
+    /// The same cells with the ANSI escape sequence of their color, which are empty unless the
+    /// output has ANSI colors and the default colors.
+    colored_hex_cells: Vec<Cell>,
+    colored_char_cells: Vec<Cell>,
+    /// The colors of the byte values if there is no classifier.
+    byte_colors: Vec<ColorType>,
+    /// The number of panels to draw.
+    panels: u64,
+    /// The number of octets per group.
+    group_size: u8,
+    /// The number of digits used to write the base.
+    base_digits: u8,
+    /// Whether the current dump is empty, which is drawn as wide as a hexadecimal one.
+    is_empty_dump: bool,
+    /// Whether to show groups in little or big endian format.
+    endianness: Endianness,
+    output_format: OutputFormat,
+    /// The custom styles of the bytes, `None` for the default categories.
+    classifier: Option<Box<dyn ByteClassifier + Send>>,
+    /// The text of the header row above the rows.
+    header: Option<String>,
+    /// The offset of the current row.
+    row_offset: u64,
+    /// The bytes of the current group that are not written yet in little endian format, `None`
+    /// for unreadable bytes.
+    group: Vec<Option<u8>>,
+    /// The number of bytes in the current row if it is incomplete.
+    partial_len: Option<usize>,
+    is_first_row: bool,
+}
+
+impl<Writer: Write> TerminalRenderer<Writer> {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        writer: Writer,
+        show_color: bool,
+        show_char_panel: bool,
+        show_position_panel: bool,
+        border_style: BorderStyle,
+        panels: u64,
+        group_size: u8,
+        base: Base,
+        endianness: Endianness,
+        character_table: CharacterTable,
+        output_format: OutputFormat,
+        classifier: Option<Box<dyn ByteClassifier + Send>>,
+        header: Option<DumpHeader>,
+    ) -> Self {
+        // the colors of HTML are CSS classes, which don't depend on the terminal
+        let show_color = show_color || output_format.is_html();
+        let byte_colors: Vec<ColorType> = (0u8..=u8::MAX).map(|i| Byte(i).color()).collect();
+        let hex_cells: Vec<Cell> = (0u8..=u8::MAX)
+            .map(|i| Cell::new(&[base.format_byte(i).as_bytes()]))
+            .collect();
+        let char_cells: Vec<Cell> = (0u8..=u8::MAX)
+            .map(|i| {
+                let c = Byte(i).as_char(character_table).to_string();
+                if output_format.is_html() {
+                    Cell::new(&[html::escape(&c).as_bytes()])
+                } else {
+                    Cell::new(&[c.as_bytes()])
+                }
+            })
+            .collect();
+        let with_colors = |cells: &[Cell]| -> Vec<Cell> {
+            if !show_color || output_format.is_html() || classifier.is_some() {
+                return Vec::new();
+            }
+            cells
+                .iter()
+                .zip(&byte_colors)
+                .map(|(cell, color)| Cell::new(&[color.ansi_bytes(), cell.as_bytes()]))
+                .collect()
+        };
+        let colored_hex_cells = with_colors(&hex_cells);
+        let colored_char_cells = with_colors(&char_cells);
+        TerminalRenderer {
+            writer,
+            buf: Vec::new(),
+            show_char_panel,
+            show_position_panel,
+            show_color,
+            curr_color: None,
+            html_span: None,
+            border_style,
+            outer_sep: border_style.outer_sep().to_string(),
+            inner_sep: border_style.inner_sep().to_string(),
+            hex_cells,
+            char_cells,
+            colored_hex_cells,
+            colored_char_cells,
+            byte_colors,
+            panels,
+            group_size,
+            base_digits: base.digits(),
+            is_empty_dump: false,
+            endianness,
+            output_format,
+            classifier,
+            header: header.as_ref().map(DumpHeader::text),
+            row_offset: 0,
+            group: Vec::with_capacity(group_size as usize),
+            partial_len: None,
+            is_first_row: true,
+        }
+    }
+
+    /// A renderer with the same settings and state that renders into a buffer, see
+    /// [`ForkRenderer`](crate::render::ForkRenderer). The classifier can't be shared, so the
+    /// renderer must not have one.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn fork(&self) -> TerminalRenderer<Vec<u8>> {
+        debug_assert!(self.classifier.is_none());
+        TerminalRenderer {
+            writer: Vec::new(),
+            buf: Vec::new(),
+            show_char_panel: self.show_char_panel,
+            show_position_panel: self.show_position_panel,
+            show_color: self.show_color,
+            curr_color: self.curr_color,
+            html_span: self.html_span,
+            border_style: self.border_style,
+            outer_sep: self.outer_sep.clone(),
+            inner_sep: self.inner_sep.clone(),
+            hex_cells: self.hex_cells.clone(),
+            char_cells: self.char_cells.clone(),
+            colored_hex_cells: self.colored_hex_cells.clone(),
+            colored_char_cells: self.colored_char_cells.clone(),
+            byte_colors: self.byte_colors.clone(),
+            panels: self.panels,
+            group_size: self.group_size,
+            base_digits: self.base_digits,
+            is_empty_dump: self.is_empty_dump,
+            endianness: self.endianness,
+            output_format: self.output_format,
+            classifier: None,
+            header: None,
+            row_offset: self.row_offset,
+            group: Vec::with_capacity(self.group_size as usize),
+            partial_len: None,
+            is_first_row: self.is_first_row,
+        }
+    }
+
+    /// Continues with the state of `fork`, which rendered the rows before the next one.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn resume(&mut self, fork: &TerminalRenderer<Vec<u8>>) {
+        self.curr_color = fork.curr_color;
+        self.html_span = fork.html_span;
+        self.row_offset = fork.row_offset;
+        self.is_first_row = fork.is_first_row;
+    }
+
+    /// Whether the renderer can be forked, which needs the default colors.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn can_fork(&self) -> bool {
+        self.classifier.is_none()
+    }
+
+    fn row_len(&self) -> usize {
+        8 * self.panels as usize
+    }
+
+    /// Writes the buffered output.
+    fn write_buf(&mut self) -> io::Result<()> {
+        self.writer.write_all(&self.buf)?;
+        self.buf.clear();
+        Ok(())
+    }
+
+    /// Switches to `color`, with an ANSI escape sequence or a `<span>` in HTML output.
+    fn write_color(&mut self, color: ColorType) -> io::Result<()> {
+        if !self.output_format.is_html() {
+            return match color {
+                ColorType::Rgb(r, g, b) => write!(self.buf, "\x1b[38;2;{r};{g};{b}m"),
+                _ => {
+                    self.buf.extend_from_slice(color.ansi_bytes());
+                    Ok(())
+                }
+            };
+        }
+        if self.html_span == Some(color) {
+            return Ok(());
+        }
+        if self.html_span.take().is_some() {
+            self.buf.extend_from_slice(b"</span>");
+        }
+        if let ColorType::Rgb(r, g, b) = color {
+            write!(self.buf, "<span style=\"color: #{r:02x}{g:02x}{b:02x}\">")?;
+            self.html_span = Some(color);
+        } else if let Some(class) = color.css_class() {
+            write!(self.buf, "<span class=\"{class}\">")?;
+            self.html_span = Some(color);
+        }
+        Ok(())
+    }
+
+    /// Switches to the color of a cell, unless it already has it.
+    fn switch_color(&mut self, color: ColorType) -> io::Result<()> {
+        if self.curr_color != Some(color) {
+            self.write_color(color)?;
+            self.curr_color = Some(color);
+        }
+        Ok(())
+    }
+
+    /// Resets the color at the end of a panel.
+    fn reset_color(&mut self) -> io::Result<()> {
+        self.curr_color = None;
+        self.write_color(ColorType::Reset)
+    }
+
+    /// The color of `byte`, the `index`th byte of the row.
+    fn byte_color(&self, index: usize, byte: u8) -> ColorType {
+        match &self.classifier {
+            Some(classifier) => classifier.style(self.row_offset + index as u64, byte).color,
+            None => self.byte_colors[byte as usize],
+        }
+    }
+
+    /// Writes the glyph of `byte`, the `index`th byte of the row.
+    fn write_glyph(&mut self, index: usize, byte: u8) {
+        let glyph = self
+            .classifier
+            .as_ref()
+            .and_then(|classifier| classifier.style(self.row_offset + index as u64, byte).glyph);
+        match glyph {
+            Some(glyph) if self.output_format.is_html() => self
+                .buf
+                .extend_from_slice(html::escape(glyph.encode_utf8(&mut [0; 4])).as_bytes()),
+            Some(glyph) => self
+                .buf
+                .extend_from_slice(glyph.encode_utf8(&mut [0; 4]).as_bytes()),
+            None => self.char_cells[byte as usize].write_to(&mut self.buf),
+        }
+    }
+
+    /// Writes the hex cell of `byte`, the `index`th byte of the row, in its color.
+    fn write_hex_cell(&mut self, index: usize, byte: u8) -> io::Result<()> {
+        if self.show_color {
+            let color = self.byte_color(index, byte);
+            if self.curr_color != Some(color) {
+                if let Some(cell) = self.colored_hex_cells.get(byte as usize) {
+                    cell.write_to(&mut self.buf);
+                    self.curr_color = Some(color);
+                    return Ok(());
+                }
+                self.switch_color(color)?;
+            }
+        }
+        self.hex_cells[byte as usize].write_to(&mut self.buf);
+        Ok(())
+    }
+
+    /// Writes the character cell of `byte`, the `index`th byte of the row, in its color.
+    fn write_char_cell(&mut self, index: usize, byte: u8) -> io::Result<()> {
+        if self.show_color {
+            let color = self.byte_color(index, byte);
+            if self.curr_color != Some(color) {
+                if let Some(cell) = self.colored_char_cells.get(byte as usize) {
+                    cell.write_to(&mut self.buf);
+                    self.curr_color = Some(color);
+                    return Ok(());
+                }
+                self.switch_color(color)?;
+            }
+        }
+        self.write_glyph(index, byte);
+        Ok(())
+    }
+
+    fn panel_sz(&self) -> usize {
+        let digits = if self.is_empty_dump {
+            2
+        } else {
+            self.base_digits as usize
+        };
+        // add one to include the trailing space of a group
+        let group_sz = digits * self.group_size as usize + 1;
+        let group_per_panel = 8 / self.group_size as usize;
+        // add one to include the leading space
+        1 + group_sz * group_per_panel
+    }
+
+    fn write_border(&mut self, border_elements: BorderElements) -> io::Result<()> {
+        let h = border_elements.horizontal_line;
+        let c = border_elements.column_separator;
+        let l = border_elements.left_corner;
+        let r = border_elements.right_corner;
+        let h8 = h.to_string().repeat(8);
+        let h_repeat = h.to_string().repeat(self.panel_sz());
+
+        if self.show_position_panel {
+            write!(self.buf, "{l}{h8}{c}")?;
+        } else {
+            write!(self.buf, "{l}")?;
+        }
+
+        for _ in 0..self.panels - 1 {
+            write!(self.buf, "{h_repeat}{c}")?;
+        }
+        if self.show_char_panel {
+            write!(self.buf, "{h_repeat}{c}")?;
+        } else {
+            write!(self.buf, "{h_repeat}")?;
+        }
+
+        if self.show_char_panel {
+            for _ in 0..self.panels - 1 {
+                write!(self.buf, "{h8}{c}")?;
+            }
+            writeln!(self.buf, "{h8}{r}")?;
+        } else {
+            writeln!(self.buf, "{r}")?;
+        }
+
+        Ok(())
+    }
+
+    /// The width of the borders in characters.
+    fn border_width(&self) -> usize {
+        let panels = self.panels as usize;
+        let mut width = 2 + panels * self.panel_sz() + panels - 1;
+        if self.show_position_panel {
+            width += 9;
+        }
+        if self.show_char_panel {
+            width += 1 + panels * 8 + panels - 1;
+        }
+        width
+    }
+
+    /// Writes the row of the header inside its own border, with `text` cut off at the end of the
+    /// row.
+    fn write_header_row(&mut self, text: &str) -> io::Result<()> {
+        if let Some(e) = self.border_style.title_elems() {
+            self.write_border(e)?;
+        }
+        let max_len = self.border_width() - 4;
+        let text: String = if text.chars().count() > max_len {
+            let ellipsis = match self.border_style {
+                BorderStyle::Ascii => "...",
+                _ => "…",
+            };
+            let len = max_len - ellipsis.chars().count();
+            text.chars().take(len).chain(ellipsis.chars()).collect()
+        } else {
+            text.into()
+        };
+        let len = text.chars().count();
+        let text = if self.output_format.is_html() {
+            html::escape(&text)
+        } else {
+            text
+        };
+        let sep = self.border_style.outer_sep();
+        writeln!(self.buf, "{sep} {text}{:1$} {sep}", "", max_len - len)
+    }
+
+    pub(crate) fn print_header(&mut self) -> io::Result<()> {
+        match self.output_format {
+            OutputFormat::Html => {
+                html::write_document_start(&mut self.buf)?;
+                self.buf.extend_from_slice(b"<pre class=\"hexyl\">\n");
+            }
+            OutputFormat::HtmlFragment => self.buf.extend_from_slice(b"<pre class=\"hexyl\">\n"),
+            _ => {}
+        }
+        if let Some(text) = self.header.take() {
+            self.write_header_row(&text)?;
+            self.header = Some(text);
+            if let Some(e) = self.border_style.title_separator_elems() {
+                self.write_border(e)?
+            }
+        } else if let Some(e) = self.border_style.header_elems() {
+            self.write_border(e)?
+        }
+        self.write_buf()
+    }
+
+    pub(crate) fn print_footer(&mut self) -> io::Result<()> {
+        if let Some(e) = self.border_style.footer_elems() {
+            self.write_border(e)?
+        }
+        match self.output_format {
+            OutputFormat::Html => {
+                self.buf.extend_from_slice(b"</pre>\n");
+                html::write_document_end(&mut self.buf)?;
+            }
+            OutputFormat::HtmlFragment => self.buf.extend_from_slice(b"</pre>\n"),
+            _ => {}
+        }
+        self.write_buf()
+    }
+
+    /// Prints the position panel, with `*` instead of the offset for squeezed rows.
+    fn print_position_panel(&mut self, offset: u64, squeezed: bool) -> io::Result<()> {
+        self.buf.extend_from_slice(self.outer_sep.as_bytes());
+        if self.show_color {
+            self.write_color(ColorType::Offset)?;
+        }
+        if self.show_position_panel {
+            if squeezed {
+                self.buf.push(b'*');
+                if self.show_color {
+                    self.write_color(ColorType::Reset)?;
+                }
+                self.buf.extend_from_slice(b"       ");
+            } else {
+                if self.output_format.is_html() {
+                    // make every row linkable by its offset
+                    write!(
+                        self.buf,
+                        "<a id=\"offset-{offset:08x}\" href=\"#offset-{offset:08x}\">"
+                    )?;
+                }
+                let byte_index: [u8; 8] = offset.to_be_bytes();
+                let mut i = 0;
+                while byte_index[i] == 0x0 && i < 4 {
+                    i += 1;
+                }
+                for &byte in byte_index.iter().skip(i) {
+                    self.buf.extend_from_slice(&[
+                        HEX_DIGITS[byte as usize >> 4],
+                        HEX_DIGITS[byte as usize & 0xf],
+                    ]);
+                }
+                if self.output_format.is_html() {
+                    self.buf.extend_from_slice(b"</a>");
+                }
+                if self.show_color {
+                    self.write_color(ColorType::Reset)?;
+                }
+            }
+            self.buf.extend_from_slice(self.outer_sep.as_bytes());
+        }
+        Ok(())
+    }
+
+    /// Prints a cell of the character panel, or a blank cell for `None`.
+    fn print_char(&mut self, i: usize, byte: Option<u8>) -> io::Result<()> {
+        match byte {
+            None => self.buf.push(b' '),
+            Some(b) => self.write_char_cell(i, b)?,
+        }
+        self.end_char(i)
+    }
+
+    /// Prints a cell of the character panel for a byte that could not be read.
+    fn print_unreadable_char(&mut self, i: usize) -> io::Result<()> {
+        if self.show_color {
+            self.switch_color(ColorType::Unreadable)?;
+        }
+        self.buf.push(b'?');
+        self.end_char(i)
+    }
+
+    /// Writes the separator after the `i`th cell of the character panel if it is the last of a
+    /// panel.
+    fn end_char(&mut self, i: usize) -> io::Result<()> {
+        if i % 8 == 7 {
+            if self.show_color {
+                self.reset_color()?;
+            }
+            let sep = if i == self.row_len() - 1 {
+                &self.outer_sep
+            } else {
+                &self.inner_sep
+            };
+            self.buf.extend_from_slice(sep.as_bytes());
+        }
+        Ok(())
+    }
+
+    pub(crate) fn print_char_panel(&mut self, line: &[u8]) -> io::Result<()> {
+        for (i, &b) in line.iter().enumerate() {
+            self.print_char(i, Some(b))?;
+        }
+        self.write_buf()
+    }
+
+    /// Prints a cell of the hex panel, or a blank cell for `None`.
+    fn print_byte(&mut self, i: usize, byte: Option<(u8, ColorType)>) -> io::Result<()> {
+        match byte {
+            None => {
+                if !self.show_position_panel && i == 0 {
+                    if self.show_color {
+                        self.write_color(ColorType::Offset)?;
+                    }
+                    self.char_cells[b'*' as usize].write_to(&mut self.buf);
+                    if self.show_color {
+                        self.write_color(ColorType::Reset)?;
+                    }
+                } else if i % (self.group_size as usize) == 0 {
+                    self.buf.push(b' ');
+                }
+                for _ in 0..self.base_digits {
+                    self.buf.push(b' ');
+                }
+            }
+            Some((b, byte_color)) => {
+                if i % (self.group_size as usize) == 0 {
+                    self.buf.push(b' ');
+                }
+                if self.show_color {
+                    self.switch_color(byte_color)?;
+                }
+                self.hex_cells[b as usize].write_to(&mut self.buf);
+            }
+        }
+        self.end_byte(i)
+    }
+
+    /// Writes the separator after the `i`th cell of the hex panel if it is the last of a panel.
+    fn end_byte(&mut self, i: usize) -> io::Result<()> {
+        // byte is last in panel
+        if i % 8 == 7 {
+            if self.show_color {
+                self.reset_color()?;
+            }
+            self.buf.push(b' ');
+            // byte is last in last panel
+            let sep = if i == self.row_len() - 1 {
+                &self.outer_sep
+            } else {
+                &self.inner_sep
+            };
+            self.buf.extend_from_slice(sep.as_bytes());
+        }
+        Ok(())
+    }
+
+    /// Writes the pending group of a little endian row, which ends before `end`.
+    fn flush_group(&mut self, end: usize) -> io::Result<()> {
+        let len = self.group.len();
+        for i in 0..len {
+            match self.group[len - 1 - i] {
+                Some(b) => {
+                    // the color is the one of the byte's own position, which is mirrored
+                    let color = self.byte_color(end - 1 - i, b);
+                    self.print_byte(end - len + i, Some((b, color)))?
+                }
+                None => self.print_unreadable_byte(end - len + i)?,
+            }
+        }
+        self.group.clear();
+        Ok(())
+    }
+
+    /// Prints a cell of the hex panel for a byte that could not be read.
+    fn print_unreadable_byte(&mut self, i: usize) -> io::Result<()> {
+        if i % (self.group_size as usize) == 0 {
+            self.buf.push(b' ');
+        }
+        if self.show_color {
+            self.switch_color(ColorType::Unreadable)?;
+        }
+        for _ in 0..self.base_digits {
+            self.buf.push(b'?');
+        }
+        self.end_byte(i)
+    }
+
+    pub(crate) fn print_bytes(&mut self, line: &[u8]) -> io::Result<()> {
+        for (i, &b) in line.iter().enumerate() {
+            self.byte(i, b)?;
+        }
+        self.flush_group(line.len())?;
+        self.write_buf()
+    }
+
+    /// Renders the hex panel of a row of readable bytes, group by group. The cells of a little
+    /// endian group are mirrored, but keep the colors of the bytes they show.
+    fn render_hex_panel(&mut self, row: &[u8], row_len: usize) -> io::Result<()> {
+        let group_size = self.group_size as usize;
+        let little_endian = self.endianness == Endianness::Little;
+        for (panel_start, panel) in (0..).step_by(8).zip(row.chunks(8)) {
+            for (group_start, group) in (panel_start..)
+                .step_by(group_size)
+                .zip(panel.chunks(group_size))
+            {
+                self.buf.push(b' ');
+                for j in 0..group.len() {
+                    let k = if little_endian {
+                        group.len() - 1 - j
+                    } else {
+                        j
+                    };
+                    self.write_hex_cell(group_start + k, group[k])?;
+                }
+            }
+            if panel.len() == 8 {
+                self.end_byte(panel_start + 7)?;
+            }
+        }
+        for i in row.len()..row_len {
+            self.print_byte(i, None)?;
+        }
+        Ok(())
+    }
+
+    /// Renders the character panel of a row of readable bytes.
+    fn render_char_panel(&mut self, row: &[u8], row_len: usize) -> io::Result<()> {
+        for (panel_start, panel) in (0..).step_by(8).zip(row.chunks(8)) {
+            for (i, &b) in (panel_start..).zip(panel) {
+                self.write_char_cell(i, b)?;
+            }
+            if panel.len() == 8 {
+                self.end_char(panel_start + 7)?;
+            }
+        }
+        for i in row.len()..row_len {
+            self.print_char(i, None)?;
+        }
+        Ok(())
+    }
+}
+
+impl<Writer: Write> Renderer for TerminalRenderer<Writer> {
+    fn begin_dump(&mut self) -> io::Result<()> {
+        self.is_empty_dump = false;
+        self.print_header()
+    }
+
+    fn empty_dump(&mut self) -> io::Result<()> {
+        self.is_empty_dump = true;
+        self.print_header()?;
+        if self.show_position_panel {
+            write!(self.buf, "{0:9}", "│")?;
+        }
+        write!(
+            self.buf,
+            "{0:2}{1:2$}{0}{0:>3$}",
+            "│",
+            "No content",
+            self.panel_sz() - 1,
+            self.panel_sz() + 1,
+        )?;
+        if self.show_char_panel {
+            write!(self.buf, "{0:>9}{0:>9}", "│")?;
+        }
+        writeln!(self.buf)?;
+        self.write_buf()
+    }
+
+    fn end_dump(&mut self) -> io::Result<()> {
+        self.print_footer()?;
+        self.writer.flush()
+    }
+
+    fn begin_row(&mut self, offset: u64) -> io::Result<()> {
+        self.row_offset = offset;
+        self.print_position_panel(offset, false)
+    }
+
+    fn byte(&mut self, index: usize, byte: u8) -> io::Result<()> {
+        match self.endianness {
+            Endianness::Big => self.print_byte(index, Some((byte, self.byte_color(index, byte)))),
+            Endianness::Little => {
+                self.group.push(Some(byte));
+                if self.group.len() == self.group_size as usize {
+                    self.flush_group(index + 1)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn unreadable_byte(&mut self, index: usize) -> io::Result<()> {
+        match self.endianness {
+            Endianness::Big => self.print_unreadable_byte(index),
+            Endianness::Little => {
+                self.group.push(None);
+                if self.group.len() == self.group_size as usize {
+                    self.flush_group(index + 1)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn partial_row(&mut self, len: usize) -> io::Result<()> {
+        self.flush_group(len)?;
+        for i in len..self.row_len() {
+            self.print_byte(i, None)?;
+        }
+        self.partial_len = Some(len);
+        Ok(())
+    }
+
+    fn char_cell(&mut self, index: usize, byte: u8) -> io::Result<()> {
+        if self.show_char_panel {
+            self.print_char(index, Some(byte))?;
+        }
+        Ok(())
+    }
+
+    fn unreadable_char(&mut self, index: usize) -> io::Result<()> {
+        if self.show_char_panel {
+            self.print_unreadable_char(index)?;
+        }
+        Ok(())
+    }
+
+    fn end_row(&mut self) -> io::Result<()> {
+        if let Some(len) = self.partial_len.take() {
+            if self.show_char_panel {
+                for i in len..self.row_len() {
+                    self.print_char(i, None)?;
+                }
+            }
+        }
+        self.buf.push(b'\n');
+        self.write_buf()?;
+        if self.is_first_row {
+            self.writer.flush()?;
+            self.is_first_row = false;
+        }
+        Ok(())
+    }
+
+    fn row(&mut self, offset: u64, row: &[u8], row_len: usize) -> io::Result<()> {
+        self.row_offset = offset;
+        self.print_position_panel(offset, false)?;
+        self.render_hex_panel(row, row_len)?;
+        if self.show_char_panel {
+            self.render_char_panel(row, row_len)?;
+        }
+        self.end_row()
+    }
+
+    fn squeeze_marker(&mut self, offset: u64) -> io::Result<()> {
+        self.print_position_panel(offset, true)?;
+        for i in 0..self.row_len() {
+            self.print_byte(i, None)?;
+        }
+        if self.show_char_panel {
+            for i in 0..self.row_len() {
+                self.print_char(i, None)?;
+            }
+        }
+        self.buf.push(b'\n');
+        self.write_buf()
+    }
+}
@@ -154,3 +154,103 @@ fn test_parse_byte_offset() {
         }
     );
 }
+
+#[test]
+fn config_file() {
+    let config = "\
+# hexyl defaults
+--border none
+
+  --panels auto --character-table codepage-437
+--variable-name 'two words'
+";
+    assert_eq!(
+        parse_config(config).unwrap(),
+        [
+            "--border",
+            "none",
+            "--panels",
+            "auto",
+            "--character-table",
+            "codepage-437",
+            "--variable-name",
+            "two words"
+        ]
+    );
+    assert!(parse_config("--variable-name 'unterminated").is_err());
+}
+
+#[test]
+fn merge_config_args() {
+    let merge = |defaults: &[&str], args: &[&str]| -> Vec<String> {
+        let defaults = defaults.iter().map(|arg| arg.to_string()).collect();
+        let args = std::iter::once("hexyl")
+            .chain(args.iter().copied())
+            .map(OsString::from)
+            .collect();
+        merge_args(defaults, args)
+            .into_iter()
+            .map(|arg| arg.into_string().unwrap())
+            .collect()
+    };
+    assert_eq!(
+        merge(&["--border", "none", "--panels=1", "-vb", "oct"], &["file"]),
+        [
+            "hexyl",
+            "--border",
+            "none",
+            "--panels=1",
+            "-v",
+            "-b",
+            "oct",
+            "file"
+        ]
+    );
+    // later options override earlier ones, under any of their names
+    assert_eq!(
+        merge(
+            &[
+                "--border",
+                "none",
+                "-n",
+                "16",
+                "--panels",
+                "1",
+                "--panels=2"
+            ],
+            &["--border=ascii", "--bytes=8", "-vb2", "file"]
+        ),
+        [
+            "hexyl",
+            "--panels=2",
+            "--border=ascii",
+            "--bytes=8",
+            "-vb2",
+            "file"
+        ]
+    );
+    assert_eq!(
+        merge(&["-vg", "4"], &["-g", "2"]),
+        ["hexyl", "-v", "-g", "2"]
+    );
+}
+
+#[test]
+fn pager_command() {
+    assert_eq!(parse_pager_command("more").unwrap(), ["more"]);
+    assert_eq!(
+        parse_pager_command("'/usr/bin/my pager' -x --title \"a b\"").unwrap(),
+        ["/usr/bin/my pager", "-x", "--title", "a b"]
+    );
+    assert_eq!(
+        parse_pager_command("/usr/bin/less -S").unwrap(),
+        [
+            "/usr/bin/less",
+            "--RAW-CONTROL-CHARS",
+            "--quit-if-one-screen",
+            "-S"
+        ]
+    );
+    assert!(parse_pager_command("").is_err());
+    assert!(parse_pager_command("less 'unclosed").is_err());
+}
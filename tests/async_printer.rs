@@ -0,0 +1,133 @@
+#![cfg(feature = "tokio")]
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use hexyl::io::ReadError;
+use hexyl::{Base, BorderStyle, ConfigError, Endianness, OutputFormat, PrinterBuilder};
+use tokio::io::{AsyncRead, ReadBuf};
+
+const EXAMPLES: [&str; 3] = ["ascii", "empty", "hello_world_elf64"];
+
+fn configure<Writer>(builder: PrinterBuilder<Writer>, config: usize) -> PrinterBuilder<Writer> {
+    match config {
+        0 => builder,
+        1 => builder.show_color(false).num_panels(1),
+        2 => builder
+            .with_border_style(BorderStyle::Ascii)
+            .group_size(4)
+            .endianness(Endianness::Little)
+            .show_position_panel(false),
+        3 => builder.with_base(Base::Binary).enable_squeezing(false),
+        4 => builder.output_format(OutputFormat::Json),
+        _ => builder.output_format(OutputFormat::CArray),
+    }
+}
+
+fn print_sync(input: &[u8], config: usize) -> Vec<u8> {
+    let mut output = vec![];
+    let mut printer = configure(PrinterBuilder::new(&mut output), config).build();
+    printer.display_offset(0x1000);
+    printer.print_all(input).unwrap();
+    output
+}
+
+async fn print_async(input: &[u8], config: usize) -> Vec<u8> {
+    let mut output = vec![];
+    let mut printer = configure(PrinterBuilder::new(&mut output), config).build_async();
+    printer.display_offset(0x1000);
+    printer.print_all(input).await.unwrap();
+    output
+}
+
+#[tokio::test]
+async fn same_output_as_sync_printer() {
+    for example in EXAMPLES {
+        let input = std::fs::read(format!("tests/examples/{example}")).unwrap();
+        for config in 0..6 {
+            assert_eq!(
+                String::from_utf8(print_async(&input, config).await).unwrap(),
+                String::from_utf8(print_sync(&input, config)).unwrap(),
+                "{example}, configuration {config}"
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn write_in_chunks() {
+    let input = std::fs::read("tests/examples/hello_world_elf64").unwrap();
+    let mut output = vec![];
+    let mut printer = PrinterBuilder::new(&mut output).build_async();
+    printer.display_offset(0x1000);
+    for chunk in input.chunks(7) {
+        printer.write(chunk).await.unwrap();
+    }
+    printer.finish().await.unwrap();
+    assert_eq!(output, print_sync(&input, 0));
+}
+
+/// Returns `data` and then fails.
+struct FailingReader<'a> {
+    data: &'a [u8],
+}
+
+impl AsyncRead for FailingReader<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if self.data.is_empty() {
+            return Poll::Ready(Err(io::Error::other("broken")));
+        }
+        let n = buf.remaining().min(self.data.len());
+        buf.put_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Poll::Ready(Ok(()))
+    }
+}
+
+async fn print_failing(data: &[u8]) -> (Vec<u8>, u64) {
+    let mut output = vec![];
+    let mut printer = PrinterBuilder::new(&mut output).build_async();
+    printer.display_offset(0x1000);
+    let err = printer.print_all(FailingReader { data }).await.unwrap_err();
+    let offset = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<ReadError>()
+        .unwrap()
+        .offset();
+    (output, offset)
+}
+
+#[tokio::test]
+async fn read_error_on_first_read() {
+    let (output, offset) = print_failing(b"").await;
+    // like the sync printer, a dump that hasn't started isn't ended
+    assert_eq!(output, b"");
+    assert_eq!(offset, 0x1000);
+}
+
+#[tokio::test]
+async fn read_error_after_data() {
+    let (output, offset) = print_failing(b"0123456789").await;
+    assert_eq!(output, print_sync(b"0123456789", 0));
+    assert_eq!(offset, 0x100a);
+}
+
+#[test]
+fn try_build_async_rejects_invalid_configuration() {
+    let error = |builder: PrinterBuilder<Vec<u8>>| builder.try_build_async().err();
+    assert_eq!(
+        error(PrinterBuilder::new(vec![]).num_panels(0)),
+        Some(ConfigError::NoPanels)
+    );
+    assert_eq!(
+        error(PrinterBuilder::new(vec![]).group_size(3)),
+        Some(ConfigError::InvalidGroupSize(3))
+    );
+    assert_eq!(error(PrinterBuilder::new(vec![])), None);
+}
@@ -0,0 +1,156 @@
+#![cfg(feature = "ffi")]
+
+use std::ffi::{c_int, c_void};
+use std::path::Path;
+use std::process::Command;
+use std::ptr;
+
+use hexyl::ffi::*;
+use hexyl::{BorderStyle, PrinterBuilder};
+
+const EXAMPLES: [&str; 3] = ["ascii", "empty", "hello_world_elf64"];
+
+unsafe extern "C" fn collect(user_data: *mut c_void, bytes: *const u8, len: usize) -> c_int {
+    let output = &mut *(user_data as *mut Vec<u8>);
+    output.extend_from_slice(std::slice::from_raw_parts(bytes, len));
+    0
+}
+
+unsafe extern "C" fn fail(_user_data: *mut c_void, _bytes: *const u8, _len: usize) -> c_int {
+    -1
+}
+
+fn config() -> HexylConfig {
+    let mut config = std::mem::MaybeUninit::uninit();
+    unsafe {
+        hexyl_config_default(config.as_mut_ptr());
+        config.assume_init()
+    }
+}
+
+/// Dumps `input` through the C API, in chunks of `chunk_size` bytes.
+fn print_ffi(config: Option<&HexylConfig>, input: &[u8], chunk_size: usize) -> Vec<u8> {
+    let mut output: Vec<u8> = vec![];
+    unsafe {
+        let printer = hexyl_printer_new(
+            config.map_or(ptr::null(), |config| config as *const _),
+            collect,
+            &mut output as *mut Vec<u8> as *mut c_void,
+        );
+        assert!(!printer.is_null());
+        for chunk in input.chunks(chunk_size) {
+            assert_eq!(hexyl_printer_write(printer, chunk.as_ptr(), chunk.len()), 0);
+        }
+        assert_eq!(hexyl_printer_finish(printer), 0);
+        hexyl_printer_free(printer);
+    }
+    output
+}
+
+#[test]
+fn same_output_as_printer() {
+    let mut ascii_config = config();
+    ascii_config.panels = 1;
+    ascii_config.group_size = 2;
+    ascii_config.border_style = HEXYL_BORDER_STYLE_ASCII;
+    ascii_config.show_color = false;
+
+    for example in EXAMPLES {
+        let input = std::fs::read(Path::new("tests/examples").join(example)).unwrap();
+
+        let mut expected = vec![];
+        PrinterBuilder::new(&mut expected)
+            .build()
+            .print_all(&input[..])
+            .unwrap();
+        assert_eq!(print_ffi(None, &input, 7), expected);
+        assert_eq!(print_ffi(Some(&config()), &input, 4096), expected);
+
+        let mut expected = vec![];
+        PrinterBuilder::new(&mut expected)
+            .num_panels(1)
+            .group_size(2)
+            .with_border_style(BorderStyle::Ascii)
+            .show_color(false)
+            .build()
+            .print_all(&input[..])
+            .unwrap();
+        assert_eq!(print_ffi(Some(&ascii_config), &input, 16), expected);
+    }
+}
+
+#[test]
+fn invalid_config() {
+    let mut config = config();
+    config.base = 42;
+    let printer = unsafe { hexyl_printer_new(&config, collect, ptr::null_mut()) };
+    assert!(printer.is_null());
+
+    let mut config = self::config();
+    config.panels = 0;
+    let printer = unsafe { hexyl_printer_new(&config, collect, ptr::null_mut()) };
+    assert!(printer.is_null());
+}
+
+#[test]
+fn failing_callback() {
+    unsafe {
+        let printer = hexyl_printer_new(ptr::null(), fail, ptr::null_mut());
+        assert_eq!(hexyl_printer_write(printer, [0; 32].as_ptr(), 32), -1);
+        assert_eq!(hexyl_printer_finish(printer), -1);
+        hexyl_printer_free(printer);
+    }
+}
+
+/// Builds the static library and a C program that uses it with the system `cc`, which takes a
+/// while, so it only runs with `cargo test --features ffi -- --ignored`.
+#[test]
+#[ignore]
+fn c_program() {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi");
+    let status = Command::new(env!("CARGO"))
+        .args([
+            "rustc",
+            "--lib",
+            "--features",
+            "ffi",
+            "--crate-type",
+            "staticlib",
+        ])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let program = target_dir.join("dump");
+    let status = Command::new("cc")
+        .args([
+            "-std=c99",
+            "-Wall",
+            "-Werror",
+            "-Iinclude",
+            "tests/ffi/dump.c",
+        ])
+        .arg(target_dir.join("debug").join("libhexyl.a"))
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&program)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let input = std::fs::read("tests/examples/hello_world_elf64").unwrap();
+    let mut expected = vec![];
+    PrinterBuilder::new(&mut expected)
+        .num_panels(1)
+        .show_color(false)
+        .build()
+        .print_all(&input[..])
+        .unwrap();
+    let output = Command::new(program)
+        .stdin(std::fs::File::open("tests/examples/hello_world_elf64").unwrap())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected);
+}
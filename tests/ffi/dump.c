@@ -0,0 +1,32 @@
+/* Dumps its standard input with one panel, through the C API of hexyl. */
+
+#include <stdio.h>
+
+#include "hexyl.h"
+
+static int write_stdout(void *user_data, const uint8_t *bytes, size_t len) {
+    (void)user_data;
+    return fwrite(bytes, 1, len, stdout) == len ? 0 : -1;
+}
+
+int main(void) {
+    HexylConfig config;
+    hexyl_config_default(&config);
+    config.panels = 1;
+    config.show_color = false;
+
+    HexylPrinter *printer = hexyl_printer_new(&config, write_stdout, NULL);
+    if (printer == NULL) {
+        return 1;
+    }
+    uint8_t buf[4096];
+    size_t len;
+    while ((len = fread(buf, 1, sizeof(buf), stdin)) > 0) {
+        if (hexyl_printer_write(printer, buf, len) != 0) {
+            return 1;
+        }
+    }
+    int status = hexyl_printer_finish(printer);
+    hexyl_printer_free(printer);
+    return status == 0 ? 0 : 1;
+}
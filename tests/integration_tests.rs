@@ -3,8 +3,16 @@ use assert_cmd::Command;
 fn hexyl() -> Command {
     let mut cmd = Command::cargo_bin("hexyl").unwrap();
     cmd.current_dir("tests/examples");
+    // don't pick up the config of the user running the tests
+    cmd.env("XDG_CONFIG_HOME", config_home("no-config"));
+    cmd.env_remove("HEXYL_OPTS");
     cmd
 }
+
+/// A config directory for the tests, which contains `hexyl/config` with `config` if it is given.
+fn config_home(name: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
 trait PrettyAssert<S>
 where
     S: AsRef<str>,
@@ -290,6 +298,16 @@ mod display_settings {
                  └─────────────────────────┴─────────────────────────┴────────┴────────┘\n",
             );
     }
+
+    #[test]
+    fn fails_on_too_many_panels() {
+        hexyl()
+            .arg("ascii")
+            .arg("--panels=2305843009213693952")
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("panels are too many"));
+    }
 }
 
 mod group_and_endianness {
@@ -785,3 +803,749 @@ mod character_table {
             );
     }
 }
+
+mod reverse {
+    use super::hexyl;
+
+    fn dump(args: &[&str]) -> Vec<u8> {
+        hexyl()
+            .arg("hello_world_elf64")
+            .arg("--color=never")
+            .args(args)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone()
+    }
+
+    #[test]
+    fn round_trip() {
+        let expected = std::fs::read("tests/examples/hello_world_elf64").unwrap();
+        for args in [
+            &[][..],
+            &["--border=ascii", "--panels=3"],
+            &["--border=none", "--no-characters"],
+            &["--group-size=4", "--endianness=little"],
+        ] {
+            hexyl()
+                .arg("--reverse")
+                .args(args)
+                .write_stdin(dump(args))
+                .assert()
+                .success()
+                .stdout(expected.clone());
+        }
+    }
+
+    #[test]
+    fn octal() {
+        hexyl()
+            .arg("--reverse")
+            .arg("--base=octal")
+            .write_stdin(
+                "┌────────┬─────────────────────────────────┬────────┐\n\
+                 │00000000│ 141 142 143                     │abc     │\n\
+                 └────────┴─────────────────────────────────┴────────┘\n",
+            )
+            .assert()
+            .success()
+            .stdout("abc");
+    }
+
+    #[test]
+    fn xxd() {
+        hexyl()
+            .arg("--reverse")
+            .write_stdin("00000000: 6865 7879 6c0a                           hexyl.\n")
+            .assert()
+            .success()
+            .stdout("hexyl\n");
+    }
+
+    #[test]
+    fn fails_on_invalid_dump() {
+        hexyl()
+            .arg("--reverse")
+            .write_stdin("│00000000│ 68 65 7g │hexyl┊\n")
+            .assert()
+            .failure();
+    }
+}
+
+mod layout {
+    use super::hexyl;
+
+    #[test]
+    fn xxd() {
+        hexyl()
+            .arg("ascii")
+            .arg("--layout=xxd")
+            .assert()
+            .success()
+            .stdout("00000000: 3031 3233 3435 3637 3839 6162 6364 650a  0123456789abcde.\n");
+    }
+
+    #[test]
+    fn xxd_zero_runs() {
+        // plain xxd doesn't squeeze
+        hexyl()
+            .arg("zeros_with_gap")
+            .arg("--layout=xxd")
+            .assert()
+            .success()
+            .stdout(
+                "00000000: 0000 0000 0000 0000 0000 0000 0000 0000  ................\n\
+                 00000010: 0000 0000 0000 0000 0000 0000 0000 0000  ................\n\
+                 00000020: 7800 0000 0000 0000 0000 0000 0000 0000  x...............\n\
+                 00000030: 0000 0000 0000 0000 0000 0000 0000 0000  ................\n\
+                 00000040: 0000 0000 0000 0000 0000 0000 0000 0000  ................\n\
+                 00000050: 0000 0000 0000 0000 0000 0000 0000 0000  ................\n\
+                 00000060: 0000 0000                                ....\n",
+            );
+        // like xxd -a
+        hexyl()
+            .arg("zeros_with_gap")
+            .arg("--layout=xxd")
+            .arg("--squeeze")
+            .assert()
+            .success()
+            .stdout(
+                "00000000: 0000 0000 0000 0000 0000 0000 0000 0000  ................\n\
+                 00000010: 0000 0000 0000 0000 0000 0000 0000 0000  ................\n\
+                 00000020: 7800 0000 0000 0000 0000 0000 0000 0000  x...............\n\
+                 00000030: 0000 0000 0000 0000 0000 0000 0000 0000  ................\n\
+                 *\n\
+                 00000060: 0000 0000                                ....\n",
+            );
+    }
+
+    #[test]
+    fn hexdump() {
+        hexyl()
+            .arg("ascii")
+            .arg("--layout=hexdump")
+            .assert()
+            .success()
+            .stdout(
+                "00000000  30 31 32 33 34 35 36 37  38 39 61 62 63 64 65 0a  |0123456789abcde.|\n\
+                 00000010\n",
+            );
+    }
+
+    #[test]
+    fn od() {
+        hexyl()
+            .arg("ascii")
+            .arg("--layout=od")
+            .assert()
+            .success()
+            .stdout(
+                "000000 30 31 32 33 34 35 36 37 38 39 61 62 63 64 65 0a  >0123456789abcde.<\n\
+                 000010\n",
+            );
+    }
+
+    #[test]
+    fn squeeze_and_display_offset() {
+        hexyl()
+            .arg("hello_world_elf64")
+            .arg("--layout=hexdump")
+            .arg("--skip=0x1010")
+            .arg("--length=0x40")
+            .arg("--display-offset=0x1000")
+            .assert()
+            .success()
+            .stdout(
+                "00002010  04 00 00 00 cd 80 b8 01  00 00 00 cd 80 00 00 00  |................|\n\
+                 00002020  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|\n\
+                 *\n\
+                 00002050\n",
+            );
+    }
+}
+
+mod output_format {
+    use super::hexyl;
+
+    #[test]
+    fn json() {
+        hexyl()
+            .arg("hello_world_elf64")
+            .arg("--length=20")
+            .arg("--output-format=json")
+            .assert()
+            .success()
+            .stdout(concat!(
+                "[\n",
+                r#"  {"offset":0,"bytes":[127,69,76,70,2,1,1,0,0,0,0,0,0,0,0,0],"hex":["7f","45","4c","46","02","01","01","00","00","00","00","00","00","00","00","00"],"chars":"•ELF•••⋄⋄⋄⋄⋄⋄⋄⋄⋄","categories":["ascii_other","ascii_printable","ascii_printable","ascii_printable","ascii_other","ascii_other","ascii_other","null","null","null","null","null","null","null","null","null"],"squeezed":false},"#,
+                "\n",
+                r#"  {"offset":16,"bytes":[2,0,62,0],"hex":["02","00","3e","00"],"chars":"•⋄>⋄","categories":["ascii_other","null","ascii_printable","null"],"squeezed":false}"#,
+                "\n]\n",
+            ));
+    }
+
+    #[test]
+    fn html_fragment() {
+        hexyl()
+            .arg("ascii")
+            .arg("--output-format=html-fragment")
+            .arg("--color=always")
+            .assert()
+            .success()
+            .stdout(concat!(
+                "<pre class=\"hexyl\">\n",
+                "┌────────┬─────────────────────────┬─────────────────────────┬────────┬────────┐\n",
+                "│<span class=\"offset\"><a id=\"offset-00000000\" href=\"#offset-00000000\">00000000</a></span>│ ",
+                "<span class=\"ascii-printable\">30 31 32 33 34 35 36 37</span> ┊ ",
+                "<span class=\"ascii-printable\">38 39 61 62 63 64 65 </span><span class=\"ascii-whitespace\">0a</span> │",
+                "<span class=\"ascii-printable\">01234567</span>┊",
+                "<span class=\"ascii-printable\">89abcde</span><span class=\"ascii-whitespace\">_</span>│\n",
+                "└────────┴─────────────────────────┴─────────────────────────┴────────┴────────┘\n",
+                "</pre>\n",
+            ));
+    }
+
+    #[test]
+    fn html_escapes_characters() {
+        hexyl()
+            .write_stdin("<a&b>")
+            .arg("--output-format=html-fragment")
+            .arg("--color=never")
+            .arg("--border=none")
+            .assert()
+            .success()
+            .stdout(concat!(
+                "<pre class=\"hexyl\">\n",
+                " <span class=\"offset\"><a id=\"offset-00000000\" href=\"#offset-00000000\">00000000</a></span>  ",
+                "<span class=\"ascii-printable\">3c 61 26 62 3e         </span>                            ",
+                "<span class=\"ascii-printable\">&lt;a&amp;b&gt;   </span>          \n",
+                "</pre>\n",
+            ));
+    }
+
+    #[test]
+    fn html_document() {
+        hexyl()
+            .arg("ascii")
+            .arg("--output-format=html")
+            .assert()
+            .success()
+            .stdout(predicates::str::starts_with("<!DOCTYPE html>\n"))
+            .stdout(predicates::str::contains(
+                "pre.hexyl .ascii-printable { color: #00cdcd; }\n",
+            ))
+            .stdout(predicates::str::ends_with("</pre>\n</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn json_empty() {
+        hexyl()
+            .arg("empty")
+            .arg("--output-format=json")
+            .assert()
+            .success()
+            .stdout("[\n]\n");
+        hexyl()
+            .arg("empty")
+            .arg("--output-format=ndjson")
+            .assert()
+            .success()
+            .stdout("");
+    }
+
+    #[test]
+    fn plain_hex() {
+        hexyl()
+            .arg("ascii")
+            .arg("--output-format=plain-hex")
+            .assert()
+            .success()
+            .stdout("3031323334353637383961626364650a\n");
+    }
+
+    #[test]
+    fn plain_hex_bytes_per_line() {
+        hexyl()
+            .arg("ascii")
+            .arg("--output-format=plain-hex")
+            .arg("--bytes-per-line=6")
+            .assert()
+            .success()
+            .stdout("303132333435\n363738396162\n6364650a\n");
+    }
+
+    #[test]
+    fn strings() {
+        for (format, expected) in [
+            ("shell-string", "$'0123456789abcde\\n'\n"),
+            ("c-string", "\"0123456789abcde\\n\"\n"),
+            ("python-string", "b'0123456789abcde\\n'\n"),
+            ("rust-string", "b\"0123456789abcde\\n\"\n"),
+        ] {
+            hexyl()
+                .arg("ascii")
+                .arg(format!("--output-format={format}"))
+                .assert()
+                .success()
+                .stdout(expected);
+        }
+    }
+
+    #[test]
+    fn c_array() {
+        hexyl()
+            .arg("ascii")
+            .arg("--output-format=c-array")
+            .assert()
+            .success()
+            .stdout(
+                "const uint8_t ascii[] = {\n  \
+                   0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x61, 0x62,\n  \
+                   0x63, 0x64, 0x65, 0x0a\n\
+                 };\n\
+                 const size_t ascii_len = 16;\n",
+            );
+    }
+
+    #[test]
+    fn array_variable_name() {
+        hexyl()
+            .arg("--output-format=rust-array")
+            .arg("--bytes-per-line=2")
+            .write_stdin("abc")
+            .assert()
+            .success()
+            .stdout("const DATA: &[u8; 3] = &[\n    0x61, 0x62,\n    0x63,\n];\n");
+
+        hexyl()
+            .arg("ascii")
+            .arg("--output-format=go-array")
+            .arg("--length=2")
+            .arg("--variable-name=digits")
+            .assert()
+            .success()
+            .stdout("var digits = []byte{\n\t0x30, 0x31,\n}\n");
+    }
+
+    #[test]
+    fn ignores_display_settings() {
+        hexyl()
+            .arg("hello_world_elf64")
+            .arg("--output-format=c-string")
+            .arg("--length=8")
+            .arg("--panels=3")
+            .arg("--color=always")
+            .assert()
+            .success()
+            .stdout("\"\\x7f\"\"ELF\\x02\\x01\\x01\\x00\"\n");
+    }
+}
+
+// reading the unmapped first page of the own memory fails with EIO
+#[cfg(target_os = "linux")]
+mod read_errors {
+    use super::hexyl;
+
+    #[test]
+    fn fails_with_offset() {
+        hexyl()
+            .arg("/proc/self/mem")
+            .arg("--length=64")
+            .arg("--display-offset=0x100")
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains(
+                "failed to read the input at offset 0x100",
+            ));
+    }
+
+    #[test]
+    fn continue_on_error() {
+        hexyl()
+            .arg("/proc/self/mem")
+            .arg("--continue-on-error")
+            .arg("--length=100")
+            .arg("--block-size=32")
+            .arg("--color=never")
+            .assert()
+            .failure()
+            .stdout(
+                "┌────────┬─────────────────────────┬─────────────────────────┬────────┬────────┐\n\
+                 │00000000│ ?? ?? ?? ?? ?? ?? ?? ?? ┊ ?? ?? ?? ?? ?? ?? ?? ?? │????????┊????????│\n\
+                 │*       │                         ┊                         │        ┊        │\n\
+                 │00000060│ ?? ?? ?? ??             ┊                         │????    ┊        │\n\
+                 └────────┴─────────────────────────┴─────────────────────────┴────────┴────────┘\n",
+            )
+            .stderr(predicates::str::contains(
+                "skipped 100 unreadable bytes of the input",
+            ));
+    }
+}
+
+mod summary {
+    use super::hexyl;
+
+    #[test]
+    fn counts_the_truncated_input() {
+        hexyl()
+            .arg("hello_world_elf64")
+            .arg("--length=40")
+            .arg("--summary")
+            .assert()
+            .success()
+            .stderr(
+                "40 bytes, 3 rows printed, 0 rows squeezed, end offset 0x28, null: 27, \
+                 ascii_printable: 6, ascii_whitespace: 0, ascii_other: 7, non_ascii: 0\n",
+            );
+    }
+
+    #[test]
+    fn counts_squeezed_rows() {
+        hexyl()
+            .arg("hello_world_elf64")
+            .arg("--skip=0x1000")
+            .arg("--length=0x100")
+            .arg("--summary")
+            .assert()
+            .success()
+            .stderr(predicates::str::starts_with(
+                "256 bytes, 4 rows printed, 13 rows squeezed, end offset 0x1100,",
+            ));
+    }
+}
+
+mod config {
+    use super::{config_home, hexyl};
+
+    /// A config directory with a config file of `contents`.
+    fn with_config(name: &str, contents: &str) -> std::path::PathBuf {
+        let home = config_home(name);
+        std::fs::create_dir_all(home.join("hexyl")).unwrap();
+        std::fs::write(home.join("hexyl").join("config"), contents).unwrap();
+        home
+    }
+
+    const CONFIG: &str = "\
+# print one panel without colors
+--panels 1 --color=never
+
+--border none
+";
+
+    #[test]
+    fn config_file() {
+        hexyl()
+            .env("XDG_CONFIG_HOME", with_config("config-file", CONFIG))
+            .arg("ascii")
+            .assert()
+            .success()
+            .stdout(
+                " 00000000  30 31 32 33 34 35 36 37  01234567 \n \
+                  00000008  38 39 61 62 63 64 65 0a  89abcde_ \n",
+            );
+    }
+
+    #[test]
+    fn environment_overrides_config_file() {
+        hexyl()
+            .env("XDG_CONFIG_HOME", with_config("environment", CONFIG))
+            .env("HEXYL_OPTS", "--border ascii --characters")
+            .arg("ascii")
+            .arg("--length=8")
+            .assert()
+            .success()
+            .stdout(
+                "+--------+-------------------------+--------+\n\
+                 |00000000| 30 31 32 33 34 35 36 37 |01234567|\n\
+                 +--------+-------------------------+--------+\n",
+            );
+    }
+
+    #[test]
+    fn command_line_overrides_environment() {
+        hexyl()
+            .env("XDG_CONFIG_HOME", with_config("command-line", CONFIG))
+            .env("HEXYL_OPTS", "--border ascii --panels 2")
+            .arg("ascii")
+            .arg("--length=8")
+            .arg("--panels=1")
+            .arg("--border=none")
+            .assert()
+            .success()
+            .stdout(" 00000000  30 31 32 33 34 35 36 37  01234567 \n");
+    }
+
+    #[test]
+    fn no_config() {
+        hexyl()
+            .env("XDG_CONFIG_HOME", with_config("no-config-option", CONFIG))
+            .env("HEXYL_OPTS", "--border ascii")
+            .arg("ascii")
+            .arg("--length=8")
+            .arg("--color=never")
+            .arg("--no-config")
+            .assert()
+            .success()
+            .stdout(predicates::str::starts_with("┌────────┬"));
+    }
+
+    #[test]
+    fn print_config_path() {
+        let home = with_config("print-path", CONFIG);
+        hexyl()
+            .env("XDG_CONFIG_HOME", &home)
+            .arg("--print-config-path")
+            .assert()
+            .success()
+            .stdout(format!("{}\n", home.join("hexyl").join("config").display()));
+    }
+
+    #[test]
+    fn invalid_config_file() {
+        hexyl()
+            .env(
+                "XDG_CONFIG_HOME",
+                with_config("invalid", "--variable-name 'spam\n"),
+            )
+            .arg("ascii")
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("failed to parse the config file"));
+    }
+}
+
+mod paging {
+    use super::hexyl;
+
+    #[test]
+    fn auto_paging_is_disabled_if_stdout_is_not_a_tty() {
+        hexyl()
+            .env("PAGER", "false")
+            .arg("ascii")
+            .arg("--plain")
+            .arg("--paging=auto")
+            .assert()
+            .success()
+            .stdout("  30 31 32 33 34 35 36 37   38 39 61 62 63 64 65 0a  \n");
+    }
+
+    #[test]
+    fn fail_if_always_paging_and_stdout_is_not_a_tty() {
+        hexyl()
+            .arg("ascii")
+            .arg("--paging=always")
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains(
+                "can't use a pager when stdout is not a TTY",
+            ));
+    }
+
+    #[test]
+    fn never_paging_overrides_pager_flag() {
+        hexyl()
+            .arg("ascii")
+            .arg("--plain")
+            .arg("--pager")
+            .arg("--paging=never")
+            .assert()
+            .success();
+    }
+}
+
+mod multiple_files {
+    use super::hexyl;
+    use predicates::prelude::*;
+
+    #[test]
+    fn headers_and_skip_and_length_per_file() {
+        hexyl()
+            .arg("ascii")
+            .arg("-")
+            .arg("empty")
+            .arg("--plain")
+            .arg("--skip=2")
+            .arg("--length=4")
+            .write_stdin("stdin data")
+            .assert()
+            .success()
+            .stdout(
+                "==> ascii <==\n  32 33 34 35                                        \n\
+                 \n==> standard input <==\n  64 69 6e 20                                        \n\
+                 \n==> empty <==\n│ No content              │                         │\n",
+            );
+    }
+
+    #[test]
+    fn single_file_has_no_header() {
+        hexyl()
+            .arg("ascii")
+            .arg("--plain")
+            .assert()
+            .success()
+            .stdout("  30 31 32 33 34 35 36 37   38 39 61 62 63 64 65 0a  \n");
+    }
+
+    #[test]
+    fn failing_files_are_reported() {
+        hexyl()
+            .arg("ascii")
+            .arg("does-not-exist")
+            .arg("ascii")
+            .arg("--plain")
+            .arg("--length=1")
+            .assert()
+            .failure()
+            .stdout(
+                "==> ascii <==\n  30                                                 \n\
+                 \n==> ascii <==\n  30                                                 \n",
+            )
+            .stderr(
+                predicates::str::contains("failed to dump does-not-exist")
+                    .and(predicates::str::contains("failed to dump 1 of the 3 files")),
+            );
+    }
+
+    #[test]
+    fn no_blank_line_before_the_first_header() {
+        hexyl()
+            .arg("does-not-exist")
+            .arg("ascii")
+            .arg("--plain")
+            .arg("--length=1")
+            .assert()
+            .failure()
+            .stdout("==> ascii <==\n  30                                                 \n");
+    }
+
+    #[test]
+    fn variable_names_of_the_files() {
+        hexyl()
+            .arg("ascii")
+            .arg("empty")
+            .arg("--output-format=c-array")
+            .assert()
+            .success()
+            .stdout(
+                predicates::str::contains("const size_t ascii_len = 16;")
+                    .and(predicates::str::contains("const size_t empty_len = 0;")),
+            );
+    }
+}
+
+mod header {
+    use super::hexyl;
+    use predicates::prelude::*;
+
+    #[test]
+    fn header_row_with_the_file_and_the_range() {
+        hexyl()
+            .arg("ascii")
+            .arg("--color=never")
+            .arg("--header")
+            .arg("--skip=2")
+            .arg("--length=4")
+            .assert()
+            .success()
+            .stdout(
+                predicates::str::starts_with(
+                    "┌──────────────────────────────────────────────────────────────────────────────┐\n\
+                     │ ascii, modified ",
+                )
+                .and(predicates::str::contains(
+                    " UTC, bytes 0x2–0x5 of 16 bytes           │\n\
+                     ├────────┬─────────────────────────┬─────────────────────────┬────────┬────────┤\n\
+                     │00000002│ 32 33 34 35             ┊                         │2345    ┊        │\n",
+                )),
+            );
+    }
+
+    #[test]
+    fn header_row_of_stdin() {
+        hexyl()
+            .arg("--color=never")
+            .arg("--header")
+            .arg("--panels=1")
+            .write_stdin("abc")
+            .assert()
+            .success()
+            .stdout(
+                "┌───────────────────────────────────────────┐\n\
+                 │ standard input                            │\n\
+                 ├────────┬─────────────────────────┬────────┤\n\
+                 │00000000│ 61 62 63                │abc     │\n\
+                 └────────┴─────────────────────────┴────────┘\n",
+            );
+    }
+}
+
+mod mmap {
+    use super::hexyl;
+
+    /// A file that is large enough to be dumped from a memory map.
+    fn large_file() -> std::path::PathBuf {
+        let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("large");
+        let contents: Vec<u8> = (0..3_000_000u32).map(|i| (i / 4000) as u8).collect();
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn same_output_as_reading() {
+        let file = large_file();
+        for args in [
+            &["--color=always"][..],
+            &["--skip=1000", "--length=1500000", "--color=never"],
+            &["--output-format=c-array"],
+        ] {
+            let read = hexyl()
+                .arg(&file)
+                .args(args)
+                .arg("--no-mmap")
+                .assert()
+                .success();
+            let mapped = hexyl().arg(&file).args(args).assert().success();
+            assert!(mapped.get_output().stdout == read.get_output().stdout);
+        }
+    }
+}
+
+mod threads {
+    use super::hexyl;
+
+    /// A file with runs of zeros around the 64 KiB chunks that the threads render.
+    fn chunked_file() -> std::path::PathBuf {
+        let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("chunked");
+        let mut contents: Vec<u8> = (0..1_500_000u32).map(|i| (i * 7 / 3) as u8).collect();
+        for boundary in (1..22).map(|k| k * 65536) {
+            contents[boundary - 40 * (boundary / 65536)..boundary + 24].fill(0);
+        }
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn same_output_as_one_thread() {
+        let file = chunked_file();
+        for args in [
+            &["--color=always"][..],
+            &[
+                "--skip=1000",
+                "--length=1200000",
+                "--color=never",
+                "--panels=3",
+            ],
+            &["--output-format=json", "--no-mmap"],
+        ] {
+            let single = hexyl().arg(&file).args(args).assert().success();
+            let threaded = hexyl()
+                .arg(&file)
+                .args(args)
+                .arg("--threads=4")
+                .assert()
+                .success();
+            assert!(threaded.get_output().stdout == single.get_output().stdout);
+        }
+    }
+}